@@ -0,0 +1,138 @@
+//! Procedural macros for [app-universe](https://docs.rs/app_universe).
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, spanned::Spanned, FnArg, Ident, ImplItem, ItemImpl, Path};
+
+/// Implements `AppUniverseCore` for the type of an impl block whose methods handle the variants of
+/// a message enum, one method per variant.
+///
+/// The message type is passed as the argument. Every method of the block handles the variant with
+/// the CamelCase version of its name, e.g. `set_title` handles `Msg::SetTitle`, and receives the
+/// fields of the variant as its arguments after `&mut self`. Unit variants are handled by methods
+/// without arguments. Struct variants aren't supported.
+///
+/// The generated `msg` matches on every handled variant without a wildcard, so a variant without a
+/// handler fails to compile. Methods that aren't handlers belong in a separate impl block.
+///
+/// ```rust
+/// use app_universe::{AppUniverse, AppUniverseCore};
+/// use app_universe_macros::app_universe_handlers;
+///
+/// struct Counter {
+///     value: u32,
+/// }
+///
+/// enum Msg {
+///     Add(u32),
+///     Reset,
+/// }
+///
+/// #[app_universe_handlers(Msg)]
+/// impl Counter {
+///     fn add(&mut self, amount: u32) {
+///         self.value += amount;
+///     }
+///
+///     fn reset(&mut self) {
+///         self.value = 0;
+///     }
+/// }
+///
+/// let universe = AppUniverse::new(Counter { value: 0 });
+/// universe.msg(Msg::Add(3));
+/// assert_eq!(universe.read().value, 3);
+/// ```
+#[proc_macro_attribute]
+pub fn app_universe_handlers(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let message = parse_macro_input!(attr as Path);
+    let item = parse_macro_input!(item as ItemImpl);
+
+    match expand(message, item) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(message: Path, item: ItemImpl) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some((_, trait_path, _)) = &item.trait_ {
+        return Err(syn::Error::new(
+            trait_path.span(),
+            "app_universe_handlers expects an inherent impl block",
+        ));
+    }
+
+    let mut arms = vec![];
+
+    for impl_item in &item.items {
+        let method = match impl_item {
+            ImplItem::Fn(method) => method,
+            _ => continue,
+        };
+
+        let signature = &method.sig;
+        let mut inputs = signature.inputs.iter();
+
+        match inputs.next() {
+            Some(FnArg::Receiver(receiver))
+                if receiver.reference.is_some() && receiver.mutability.is_some() => {}
+            _ => {
+                return Err(syn::Error::new(
+                    signature.span(),
+                    "message handlers must take `&mut self`, move other methods to a separate impl block",
+                ))
+            }
+        }
+
+        let handler = &signature.ident;
+        let variant = Ident::new(&to_camel_case(&handler.to_string()), handler.span());
+        let fields: Vec<_> = (0..inputs.len())
+            .map(|index| format_ident!("field_{}", index))
+            .collect();
+
+        arms.push(if fields.is_empty() {
+            quote! { #message::#variant => self.#handler(), }
+        } else {
+            quote! { #message::#variant(#(#fields),*) => self.#handler(#(#fields),*), }
+        });
+    }
+
+    if arms.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "app_universe_handlers needs at least one handler method",
+        ));
+    }
+
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let self_ty = &item.self_ty;
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics ::app_universe::AppUniverseCore for #self_ty #where_clause {
+            type Message = #message;
+
+            fn msg(&mut self, message: Self::Message) {
+                match message {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+// Turns a snake_case method name into the CamelCase name of its variant.
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,19 @@
+use app_universe_macros::app_universe_handlers;
+
+struct Counter {
+    value: u32,
+}
+
+enum Msg {
+    Add(u32),
+    Reset,
+}
+
+#[app_universe_handlers(Msg)]
+impl Counter {
+    fn add(&mut self, amount: u32) {
+        self.value += amount;
+    }
+}
+
+fn main() {}
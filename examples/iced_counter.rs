@@ -0,0 +1,86 @@
+//! A counter whose state lives in a universe, with Iced redrawing whenever the universe notifies.
+//!
+//! Run it with `cargo run --example iced_counter --features iced`.
+
+use app_universe::{forward_to_universe, AppUniverse, AppUniverseCore};
+use iced::{
+    widget::{button, column, text},
+    Element, Subscription, Task,
+};
+
+struct Counter {
+    value: u32,
+}
+
+enum Msg {
+    Increment(u32),
+}
+
+impl AppUniverseCore for Counter {
+    type Message = Msg;
+
+    fn msg(&mut self, message: Self::Message) {
+        match message {
+            Msg::Increment(amount) => self.value += amount,
+        }
+    }
+}
+
+struct App {
+    universe: AppUniverse<Counter>,
+    // The version the subscription last reported.
+    version: u64,
+}
+
+#[derive(Debug, Clone)]
+enum Event {
+    Increment,
+    UniverseChanged(u64),
+}
+
+impl App {
+    fn new() -> (Self, Task<Event>) {
+        let universe = AppUniverse::new(Counter { value: 0 });
+        let app = App {
+            universe,
+            version: 0,
+        };
+        (app, Task::none())
+    }
+
+    fn update(&mut self, event: Event) -> Task<Event> {
+        match event {
+            Event::Increment => forward_to_universe(&self.universe, Msg::Increment(1)),
+            // Iced redraws after every update, so this only has to remember the version.
+            Event::UniverseChanged(version) => {
+                self.version = version;
+                Task::none()
+            }
+        }
+    }
+
+    fn view(&self) -> Element<'_, Event> {
+        let value = self.universe.read().value;
+
+        column![
+            text(format!("Counter value is {}", value)),
+            text(format!("Version {}", self.version)),
+            button("Increment").on_press(Event::Increment),
+        ]
+        .spacing(10)
+        .padding(20)
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Event> {
+        self.universe
+            .iced_subscription()
+            .map(Event::UniverseChanged)
+    }
+}
+
+fn main() -> iced::Result {
+    iced::application("Counter", App::update, App::view)
+        .subscription(App::subscription)
+        .run_with(App::new)
+}
@@ -0,0 +1,43 @@
+//! A counter whose state lives in a universe and is read through a Sycamore signal.
+//!
+//! Run it with `cargo run --example sycamore_counter --features sycamore`.
+
+use app_universe::{AppUniverse, AppUniverseCore};
+use sycamore::reactive::{create_effect, create_scope_immediate};
+
+#[derive(Clone)]
+struct Counter {
+    value: u32,
+}
+
+enum Msg {
+    Increment(u32),
+}
+
+impl AppUniverseCore for Counter {
+    type Message = Msg;
+
+    fn msg(&mut self, message: Self::Message) {
+        match message {
+            Msg::Increment(amount) => self.value += amount,
+        }
+    }
+}
+
+fn main() {
+    let universe = AppUniverse::new(Counter { value: 0 });
+
+    create_scope_immediate(|cx| {
+        let counter = universe.to_sycamore_signal(cx);
+
+        create_effect(cx, move || {
+            println!("Counter value is {}", counter.get().value);
+        });
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(2));
+    });
+
+    // The scope is disposed, so this no longer reaches the signal.
+    universe.msg(Msg::Increment(3));
+}
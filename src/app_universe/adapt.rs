@@ -0,0 +1,88 @@
+use super::AppUniverseCore;
+use std::ops::Deref;
+
+/// An `Adapted` core embeds a child core, e.g. one provided by a library, in a parent core whose
+/// messages wrap the child's messages. It's created with `adapt`.
+///
+/// The parent keeps it as a field and forwards its own messages to it, and reads reach the child
+/// through that field, e.g. `universe.read().counter.value`, since `Adapted` derefs to the child.
+/// The child can only be changed by forwarding messages to it.
+///
+/// ```rust
+/// # use app_universe::{adapt, Adapted, AppUniverse, AppUniverseCore};
+/// struct Counter { value: u32 }
+///
+/// impl AppUniverseCore for Counter {
+///     type Message = u32;
+///     fn msg(&mut self, amount: u32) { self.value += amount; }
+/// }
+///
+/// enum AppMsg {
+///     Counter(u32),
+///     Rename(String),
+/// }
+///
+/// struct App {
+///     name: String,
+///     counter: Adapted<Counter, AppMsg>,
+/// }
+///
+/// impl AppUniverseCore for App {
+///     type Message = AppMsg;
+///
+///     fn msg(&mut self, message: AppMsg) {
+///         if let Err(message) = self.counter.forward(message) {
+///             if let AppMsg::Rename(name) = message {
+///                 self.name = name;
+///             }
+///         }
+///     }
+/// }
+///
+/// let universe = AppUniverse::new(App {
+///     name: String::from("app"),
+///     counter: adapt(Counter { value: 0 }, |message| match message {
+///         AppMsg::Counter(amount) => Ok(amount),
+///         other => Err(other),
+///     }),
+/// });
+///
+/// universe.msg(AppMsg::Counter(2));
+/// assert_eq!(universe.read().counter.value, 2);
+/// ```
+pub struct Adapted<C: AppUniverseCore, M> {
+    core: C,
+    extract: fn(M) -> Result<C::Message, M>,
+}
+
+/// Wraps `core` for a parent whose messages are `M`. `extract` returns the child's message for the
+/// parent messages meant for the child, and hands every other message back.
+pub fn adapt<C: AppUniverseCore, M>(
+    core: C,
+    extract: fn(M) -> Result<C::Message, M>,
+) -> Adapted<C, M> {
+    Adapted { core, extract }
+}
+
+impl<C: AppUniverseCore, M> Adapted<C, M> {
+    /// Passes `message` on to the child's `msg` if it's meant for the child, and returns it back
+    /// otherwise so the parent can handle it.
+    pub fn forward(&mut self, message: M) -> Result<(), M> {
+        let message = (self.extract)(message)?;
+        self.core.msg(message);
+        Ok(())
+    }
+
+    /// Returns the child core.
+    pub fn into_inner(self) -> C {
+        self.core
+    }
+}
+
+impl<C: AppUniverseCore, M> Deref for Adapted<C, M> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.core
+    }
+}
@@ -0,0 +1,121 @@
+use super::{AppUniverse, AppUniverseCore, UniverseError, UniverseSubscription};
+use std::{future::Future, pin::Pin, rc::Rc};
+
+/// A boxed future as it's handed to a `Spawner`.
+pub type LocalBoxFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+/// A `Spawner` runs the futures created by `AppUniverse::dispatch_async` on an executor.
+///
+/// It's implemented for any `Fn(LocalBoxFuture)`, so a closure that forwards to the executor of
+/// your choice (e.g. `wasm_bindgen_futures::spawn_local`) is enough.
+pub trait Spawner {
+    /// Runs `future` to completion in the background.
+    fn spawn(&self, future: LocalBoxFuture);
+}
+
+impl<F: Fn(LocalBoxFuture)> Spawner for F {
+    fn spawn(&self, future: LocalBoxFuture) {
+        self(future)
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Sets the spawner used by `dispatch_async`.
+    ///
+    /// With the `wasm` feature on `wasm32`, futures are spawned with
+    /// `wasm_bindgen_futures::spawn_local` until a spawner is set.
+    pub fn set_spawner(&self, spawner: impl Spawner + 'static) {
+        *self.inner.spawner.borrow_mut() = Some(Rc::new(spawner));
+    }
+
+    /// Spawns `future` and dispatches the message it resolves to.
+    ///
+    /// The future runs on the spawner set with `set_spawner`, the universe handle it captures is a
+    /// regular clone. If it is spawned while a traced dispatch chain is being processed, the message
+    /// it resolves to belongs to the same chain and carries the same `DispatchId`.
+    ///
+    /// On the web the future runs on the browser's event loop, e.g. to dispatch the result of a
+    /// request:
+    ///
+    /// ```rust,ignore
+    /// use wasm_bindgen::JsCast;
+    /// use wasm_bindgen_futures::JsFuture;
+    ///
+    /// universe.dispatch_async(async {
+    ///     let window = web_sys::window().unwrap();
+    ///     let response = JsFuture::from(window.fetch_with_str("/api/todos")).await;
+    ///     let text = match response {
+    ///         Ok(response) => match response.unchecked_into::<web_sys::Response>().text() {
+    ///             Ok(text) => JsFuture::from(text).await.ok().and_then(|text| text.as_string()),
+    ///             Err(_) => None,
+    ///         },
+    ///         Err(_) => None,
+    ///     };
+    ///
+    ///     match text {
+    ///         Some(body) => Msg::TodosLoaded(body),
+    ///         None => Msg::TodosFailed,
+    ///     }
+    /// })?;
+    /// ```
+    pub fn dispatch_async(
+        &self,
+        future: impl Future<Output = U::Message> + 'static,
+    ) -> Result<(), UniverseError> {
+        let spawner = self.spawner()?;
+
+        let universe = self.clone();
+        let id = self.inner.dispatch_queue.current_id();
+
+        spawner.spawn(Box::pin(async move {
+            let message = future.await;
+            universe.dispatch(vec![message], id);
+        }));
+
+        Ok(())
+    }
+
+    /// This function takes an async subscriber function that runs anytime the state changes, and
+    /// spawns the future it returns on the spawner set with `set_spawner`, e.g. to persist the
+    /// state without blocking the dispatch.
+    ///
+    /// Notifications don't wait for the futures, so dispatching stays synchronous. Futures of
+    /// consecutive notifications may run concurrently and complete out of order, so a future
+    /// should read the state it needs when it's created rather than assume it's still current
+    /// when it finishes.
+    ///
+    /// Returns an error if no spawner is available.
+    pub fn subscribe_async(
+        &mut self,
+        mut subscriber_fn: Box<dyn FnMut(AppUniverse<U>) -> LocalBoxFuture>,
+    ) -> Result<UniverseSubscription<U>, UniverseError> {
+        self.spawner()?;
+
+        Ok(self.subscribe(move |universe| {
+            let future = subscriber_fn(universe.clone());
+            match universe.spawner() {
+                Ok(spawner) => spawner.spawn(future),
+                Err(error) => log::error!("Async subscriber was not spawned: {}", error),
+            }
+        }))
+    }
+
+    pub(crate) fn spawner(&self) -> Result<Rc<dyn Spawner>, UniverseError> {
+        match self.inner.spawner.borrow().clone() {
+            Some(spawner) => Ok(spawner),
+            None => default_spawner(),
+        }
+    }
+}
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+fn default_spawner() -> Result<Rc<dyn Spawner>, UniverseError> {
+    Ok(Rc::new(|future: LocalBoxFuture| {
+        wasm_bindgen_futures::spawn_local(future)
+    }))
+}
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+fn default_spawner() -> Result<Rc<dyn Spawner>, UniverseError> {
+    Err(UniverseError::NoSpawner)
+}
@@ -0,0 +1,103 @@
+use super::{dispatch::QueuedDispatch, AppUniverse, AppUniverseCore, UniverseError};
+use std::{collections::HashMap, fmt::Display, future::Future};
+
+/// The status of an async operation started with `AppUniverse::dispatch_async_tracked`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsyncStatus {
+    /// The operation is still running.
+    Loading,
+    /// The operation resolved and its message was dispatched.
+    Loaded,
+    /// The operation failed with the given error message.
+    Failed(String),
+}
+
+// The status of every key, along with the generation of the operation that set it so that
+// operations which have been superseded by a newer one for the same key are ignored.
+#[derive(Default)]
+pub(crate) struct AsyncStatusTable {
+    statuses: HashMap<String, (u64, AsyncStatus)>,
+    next_generation: u64,
+}
+
+impl AsyncStatusTable {
+    fn start(&mut self, key: String) -> u64 {
+        self.next_generation += 1;
+        self.statuses
+            .insert(key, (self.next_generation, AsyncStatus::Loading));
+        self.next_generation
+    }
+
+    // Returns `false` if the operation for `generation` is no longer the latest one for `key`.
+    fn finish(&mut self, key: &str, generation: u64, status: AsyncStatus) -> bool {
+        match self.statuses.get_mut(key) {
+            Some(entry) if entry.0 == generation => {
+                entry.1 = status;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Spawns `future` like `dispatch_async` and tracks its status under `key`.
+    ///
+    /// The status is `Loading` straight away and becomes `Loaded` (and the message is dispatched) or
+    /// `Failed` when the future resolves. Subscribers are notified on each of these transitions,
+    /// although the version only moves when a message is applied.
+    ///
+    /// A key scopes one independent operation, e.g. `"fetch-products"`. Starting a new operation with
+    /// a key that is already in use replaces its status, and the older operation no longer updates it
+    /// when it finishes (its message is still dispatched).
+    pub fn dispatch_async_tracked<E: Display>(
+        &self,
+        key: impl Into<String>,
+        future: impl Future<Output = Result<U::Message, E>> + 'static,
+    ) -> Result<(), UniverseError> {
+        let spawner = self.spawner()?;
+
+        let key = key.into();
+        let generation = self.inner.async_statuses.borrow_mut().start(key.clone());
+        let id = self.inner.dispatch_queue.current_id();
+
+        self.enqueue(QueuedDispatch::notification(id));
+
+        let universe = self.clone();
+        spawner.spawn(Box::pin(async move {
+            match future.await {
+                Ok(message) => {
+                    universe.inner.async_statuses.borrow_mut().finish(
+                        &key,
+                        generation,
+                        AsyncStatus::Loaded,
+                    );
+                    universe.dispatch(vec![message], id);
+                }
+                Err(error) => {
+                    let changed = universe.inner.async_statuses.borrow_mut().finish(
+                        &key,
+                        generation,
+                        AsyncStatus::Failed(error.to_string()),
+                    );
+                    if changed {
+                        universe.enqueue(QueuedDispatch::notification(id));
+                    }
+                }
+            }
+        }));
+
+        Ok(())
+    }
+
+    /// Returns the status of the async operation tracked under `key`, or `None` if no operation
+    /// was started with that key.
+    pub fn async_status(&self, key: &str) -> Option<AsyncStatus> {
+        self.inner
+            .async_statuses
+            .borrow()
+            .statuses
+            .get(key)
+            .map(|(_, status)| status.clone())
+    }
+}
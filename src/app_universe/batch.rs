@@ -0,0 +1,39 @@
+use super::{AppUniverse, AppUniverseCore, SubscriberCtx, UniverseSubscription};
+
+/// A `BatchInfo` summarises the messages applied since subscribers were last notified, e.g. by a
+/// `msg_batch`, a transaction or a deferred notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchInfo {
+    /// How many messages were applied, `1` for a single `msg`.
+    pub message_count: u64,
+    /// The version subscribers were last notified of.
+    pub start_version: u64,
+    /// The version subscribers are notified of now.
+    pub end_version: u64,
+}
+
+impl BatchInfo {
+    pub(crate) fn since<U: AppUniverseCore>(start_version: u64, universe: &AppUniverse<U>) -> Self {
+        let end_version = universe.inner.version.get();
+
+        BatchInfo {
+            message_count: end_version - start_version,
+            start_version,
+            end_version,
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function takes a subscriber function that runs anytime the state changes and also
+    /// receives a `BatchInfo` about the messages applied since the previous notification, e.g. to
+    /// decide how much to recompute.
+    pub fn subscribe_batch(
+        &mut self,
+        mut subscriber_fn: Box<dyn FnMut(BatchInfo, AppUniverse<U>)>,
+    ) -> UniverseSubscription<U> {
+        self.subscribe_with_ctx(Box::new(move |ctx: &SubscriberCtx<'_, U>| {
+            subscriber_fn(ctx.batch(), ctx.universe.clone())
+        }))
+    }
+}
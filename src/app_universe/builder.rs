@@ -0,0 +1,70 @@
+use super::{AppUniverse, AppUniverseCore, NotificationStrategy};
+
+// Reports whether the state differs from the one it saw last time, and remembers it.
+pub(crate) type ChangeDetector<U> = Box<dyn FnMut(&U) -> bool>;
+
+/// The `AppUniverseBuilder` configures a universe before it's created.
+/// It's obtained from `AppUniverse::builder`.
+pub struct AppUniverseBuilder<U: AppUniverseCore> {
+    core: U,
+    subscription_capacity: usize,
+    notification_strategy: NotificationStrategy,
+    change_detector: Option<ChangeDetector<U>>,
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverseBuilder<U> {
+    /// Reserves room for `capacity` subscriptions, see `AppUniverse::with_subscription_capacity`.
+    pub fn subscription_capacity(mut self, capacity: usize) -> Self {
+        self.subscription_capacity = capacity;
+        self
+    }
+
+    /// Sets the strategy that decides when subscribers are notified.
+    pub fn notification_strategy(mut self, strategy: NotificationStrategy) -> Self {
+        self.notification_strategy = strategy;
+        self
+    }
+
+    /// Selects `NotificationStrategy::OnChange`, so subscribers are only notified when the state
+    /// differs from the state of the previous notification.
+    pub fn notify_on_change(mut self) -> Self
+    where
+        U: PartialEq + Clone,
+    {
+        let mut last_notified = self.core.clone();
+
+        self.notification_strategy = NotificationStrategy::OnChange;
+        self.change_detector = Some(Box::new(move |state: &U| {
+            if *state == last_notified {
+                return false;
+            }
+
+            last_notified = state.clone();
+            true
+        }));
+        self
+    }
+
+    /// Creates the universe.
+    pub fn build(self) -> AppUniverse<U> {
+        let universe =
+            AppUniverse::with_subscription_capacity(self.core, self.subscription_capacity);
+
+        universe.set_notification_strategy(self.notification_strategy);
+        *universe.inner.change_detector.borrow_mut() = self.change_detector;
+
+        universe
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Returns a builder for a universe around `universe_core`.
+    pub fn builder(universe_core: U) -> AppUniverseBuilder<U> {
+        AppUniverseBuilder {
+            core: universe_core,
+            subscription_capacity: 0,
+            notification_strategy: NotificationStrategy::default(),
+            change_detector: None,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use super::{dispatch::QueuedDispatch, AppUniverse, AppUniverseCore};
+use std::cell::Ref;
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function is used in tests to capture dispatched messages instead of applying them, so
+    /// a test can assert on what was dispatched.
+    ///
+    /// Messages dispatched after this are added to the buffer returned by `captured_messages`
+    /// until `drain_captured_and_apply` is called, the state, the version and the subscribers are
+    /// left alone. Dispatches that compute their messages from the state, like `dispatch_fold`,
+    /// are still applied.
+    pub fn start_capturing(&self) {
+        let mut captured = self.inner.captured.borrow_mut();
+        if captured.is_none() {
+            *captured = Some(vec![]);
+        }
+    }
+
+    /// This function is used in tests to read the messages captured since `start_capturing`, in
+    /// the order they were dispatched.
+    pub fn captured_messages(&self) -> Ref<'_, [U::Message]> {
+        Ref::map(self.inner.captured.borrow(), |captured| match captured {
+            Some(messages) => messages.as_slice(),
+            None => &[],
+        })
+    }
+
+    /// This function is used in tests to stop capturing and then apply the captured messages for
+    /// real, in order and through `msg`.
+    ///
+    /// Every message is applied like a separate `msg` call, so the version goes up by one and
+    /// subscribers are notified once per message. The buffer is empty afterwards.
+    pub fn drain_captured_and_apply(&self) {
+        let captured = self.inner.captured.borrow_mut().take().unwrap_or_default();
+
+        for message in captured {
+            self.msg(message);
+        }
+    }
+
+    // Moves the messages of `dispatch` into the capture buffer, or returns it if it has to be
+    // applied.
+    pub(crate) fn capture(&self, dispatch: QueuedDispatch<U>) -> Option<QueuedDispatch<U>> {
+        match self.inner.captured.borrow_mut().as_mut() {
+            Some(captured) if dispatch.producer.is_none() && !dispatch.messages.is_empty() => {
+                captured.extend(dispatch.messages);
+                None
+            }
+            _ => Some(dispatch),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use super::{AppUniverse, AppUniverseCore};
+
+/// A `ChangeCursor` remembers the last version of a universe that a polling consumer has seen.
+///
+/// It's intended for consumers that poll the universe every frame (immediate-mode GUIs, game loops)
+/// instead of subscribing. Pass it to `AppUniverse::poll_changes` or `AppUniverse::read_if_changed`
+/// to find out whether anything changed since the previous frame.
+///
+/// A cursor created with `ChangeCursor::new` has not seen any version yet, so the first poll always
+/// reports a change. Use `AppUniverse::change_cursor` to start from the current version instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChangeCursor {
+    last_seen: Option<u64>,
+}
+
+impl ChangeCursor {
+    /// Creates a cursor that hasn't seen any version.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn at(version: u64) -> Self {
+        ChangeCursor {
+            last_seen: Some(version),
+        }
+    }
+
+    /// Returns the last version this cursor has seen, if any.
+    pub fn last_seen(&self) -> Option<u64> {
+        self.last_seen
+    }
+
+    // Moves the cursor to `version` and reports whether that was a change.
+    pub(crate) fn advance(&mut self, version: u64) -> bool {
+        let changed = self.last_seen != Some(version);
+        self.last_seen = Some(version);
+        changed
+    }
+}
+
+impl<U: AppUniverseCore + Clone + 'static> AppUniverse<U> {
+    /// Returns the current version together with a clone of the state if the state changed since
+    /// `last_version`, and `None` otherwise.
+    ///
+    /// It's meant for renderers that can't hold on to a `Ref` for a whole frame. Pass the version
+    /// returned by the previous call to poll the next frame, e.g. `0` before the first one.
+    ///
+    /// Every change clones the whole state. If only a few fields are needed, use `changed_since` and
+    /// copy them out of `read`, or poll a `ChangeCursor` with `read_if_changed` instead.
+    pub fn poll_changed(&self, last_version: u64) -> Option<(u64, U)> {
+        if self.changed_since(last_version) {
+            let state = self.read();
+            Some((self.inner.version.get(), state.clone()))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+
+// A single pending `wait_for_change` call. The version is filled in by the
+// notification that resolves it.
+#[derive(Default)]
+pub(crate) struct WaiterSlot {
+    version: Option<u64>,
+    waker: Option<Waker>,
+}
+
+type WaiterSlotParameter = Rc<RefCell<WaiterSlot>>;
+
+/// The list of pending waiters shared between all clones of a universe.
+pub(crate) type ChangeWaiters = Rc<RefCell<Vec<WaiterSlotParameter>>>;
+
+/// The future returned by `AppUniverse::wait_for_change`.
+/// It resolves with the version of the universe at the first notification
+/// that happens after it was created.
+pub(crate) struct WaitForChange {
+    slot: WaiterSlotParameter,
+    waiters: Weak<RefCell<Vec<WaiterSlotParameter>>>,
+}
+
+impl WaitForChange {
+    // The slot is registered immediately so that notifications that happen
+    // before the first poll still resolve the future.
+    pub(crate) fn register(waiters: &ChangeWaiters) -> Self {
+        let slot = Rc::new(RefCell::new(WaiterSlot::default()));
+        waiters.borrow_mut().push(slot.clone());
+
+        WaitForChange {
+            slot,
+            waiters: Rc::downgrade(waiters),
+        }
+    }
+}
+
+impl Future for WaitForChange {
+    type Output = u64;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.borrow_mut();
+
+        match slot.version {
+            Some(version) => Poll::Ready(version),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for WaitForChange {
+    fn drop(&mut self) {
+        if let Some(waiters) = self.waiters.upgrade() {
+            waiters
+                .borrow_mut()
+                .retain(|slot| !Rc::ptr_eq(slot, &self.slot));
+        }
+    }
+}
+
+/// Resolves every pending waiter with `version`.
+pub(crate) fn wake_all(waiters: &ChangeWaiters, version: u64) {
+    // The list is taken before waking so that a waker which synchronously
+    // registers a new waiter doesn't get resolved by this same notification.
+    let pending = std::mem::take(&mut *waiters.borrow_mut());
+
+    for slot in pending {
+        let waker = {
+            let mut slot = slot.borrow_mut();
+            slot.version = Some(version);
+            slot.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
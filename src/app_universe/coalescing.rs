@@ -0,0 +1,109 @@
+use super::{AppUniverse, AppUniverseCore, UniverseError};
+use std::{any::Any, rc::Rc, time::Duration};
+
+/// A `Scheduler` runs a task once a delay has passed, it defines the window of
+/// `AppUniverse::dispatch_coalesced`.
+///
+/// It's implemented for any `Fn(Duration, Box<dyn FnOnce()>)`, so a closure that forwards to the
+/// timer of your event loop (e.g. `setTimeout` or `glib::timeout_add_local_once`) is enough.
+pub trait Scheduler {
+    /// Runs `task` once `delay` has passed.
+    fn schedule(&self, delay: Duration, task: Box<dyn FnOnce()>);
+}
+
+impl<F: Fn(Duration, Box<dyn FnOnce()>)> Scheduler for F {
+    fn schedule(&self, delay: Duration, task: Box<dyn FnOnce()>) {
+        self(delay, task)
+    }
+}
+
+// A key of `dispatch_coalesced`, keys of different types are never equal.
+trait CoalesceKey {
+    fn as_any(&self) -> &dyn Any;
+    fn same_key(&self, other: &dyn CoalesceKey) -> bool;
+}
+
+impl<K: PartialEq + 'static> CoalesceKey for K {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn same_key(&self, other: &dyn CoalesceKey) -> bool {
+        other.as_any().downcast_ref::<K>() == Some(self)
+    }
+}
+
+// The latest message for every key, in the order the keys first showed up in the window.
+pub(crate) struct Coalescer<U: AppUniverseCore> {
+    window: Duration,
+    scheduler: Option<Rc<dyn Scheduler>>,
+    pending: Vec<(Box<dyn CoalesceKey>, U::Message)>,
+}
+
+impl<U: AppUniverseCore> Default for Coalescer<U> {
+    fn default() -> Self {
+        Coalescer {
+            window: Duration::ZERO,
+            scheduler: None,
+            pending: vec![],
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Sets the window of `dispatch_coalesced` and the scheduler that ends it.
+    pub fn set_coalescing_window(&self, window: Duration, scheduler: impl Scheduler + 'static) {
+        let mut coalescer = self.inner.coalescer.borrow_mut();
+        coalescer.window = window;
+        coalescer.scheduler = Some(Rc::new(scheduler));
+    }
+
+    /// Dispatches `msg` at the end of the current coalescing window, unless a newer message with
+    /// the same `key` arrives before then.
+    ///
+    /// Only the last message for every key is applied, the ones before it are dropped. When the
+    /// window ends the remaining messages are applied with `msg_batch`, in the order their keys were
+    /// first dispatched, so subscribers are notified once. The first message after a window ended
+    /// starts a new one.
+    pub fn dispatch_coalesced(
+        &self,
+        key: impl PartialEq + 'static,
+        msg: U::Message,
+    ) -> Result<(), UniverseError> {
+        let mut coalescer = self.inner.coalescer.borrow_mut();
+        let scheduler = coalescer
+            .scheduler
+            .clone()
+            .ok_or(UniverseError::NoScheduler)?;
+
+        if let Some(pending) = coalescer
+            .pending
+            .iter_mut()
+            .find(|(pending_key, _)| key.same_key(pending_key.as_ref()))
+        {
+            pending.1 = msg;
+            return Ok(());
+        }
+
+        let starts_window = coalescer.pending.is_empty();
+        coalescer.pending.push((Box::new(key), msg));
+        let window = coalescer.window;
+        drop(coalescer);
+
+        if starts_window {
+            let universe = self.downgrade();
+            scheduler.schedule(
+                window,
+                Box::new(move || {
+                    if let Some(universe) = universe.upgrade() {
+                        let pending =
+                            std::mem::take(&mut universe.inner.coalescer.borrow_mut().pending);
+                        universe.msg_batch(pending.into_iter().map(|(_, msg)| msg));
+                    }
+                }),
+            );
+        }
+
+        Ok(())
+    }
+}
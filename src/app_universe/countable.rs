@@ -0,0 +1,25 @@
+use super::{AppUniverse, AppUniverseCore};
+
+/// `Countable` is implemented by cores that wrap a collection, so `AppUniverse::len` and
+/// `AppUniverse::is_empty` can be called on their universes.
+pub trait Countable {
+    /// Returns the number of items in the core.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the core holds no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<U: AppUniverseCore + Countable + 'static> AppUniverse<U> {
+    /// Returns the number of items in the state. It's a shorthand for `read().len()`.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Returns `true` if the state holds no items. It's a shorthand for `read().is_empty()`.
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+}
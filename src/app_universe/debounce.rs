@@ -0,0 +1,99 @@
+use super::{AppUniverse, AppUniverseCore, Scheduler, WeakAppUniverse};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
+
+// The message waiting for the delay to pass, with the number of the dispatch it came from.
+type Pending<M> = Rc<RefCell<Option<(u64, M)>>>;
+
+/// A `Debouncer` dispatches a message once no newer message was debounced for a delay, e.g. to
+/// run a search after the user stopped typing. It's created with `AppUniverse::debouncer`.
+///
+/// Every call to `dispatch` replaces the pending message and restarts the delay, so only the last
+/// message of a burst is applied.
+pub struct Debouncer<U: AppUniverseCore> {
+    universe: WeakAppUniverse<U>,
+    delay: Duration,
+    scheduler: Rc<dyn Scheduler>,
+    pending: Pending<U::Message>,
+    dispatched: Cell<u64>,
+}
+
+/// A `DebounceHandle` refers to a message passed to `Debouncer::dispatch`, and can cancel it
+/// before it's applied.
+pub struct DebounceHandle<U: AppUniverseCore> {
+    pending: Pending<U::Message>,
+    dispatch: u64,
+}
+
+impl<U: AppUniverseCore> DebounceHandle<U> {
+    /// Discards the message if it's still waiting, so it's never applied. Returns `false` if it
+    /// was already applied or replaced by a newer message.
+    pub fn cancel(&self) -> bool {
+        let mut pending = self.pending.borrow_mut();
+        match pending.as_ref() {
+            Some((dispatch, _)) if *dispatch == self.dispatch => {
+                pending.take();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> Debouncer<U> {
+    /// Dispatches `msg` once `delay` has passed without another call to `dispatch`. A message that
+    /// is still waiting is replaced and the delay starts over.
+    pub fn dispatch(&self, msg: U::Message) -> DebounceHandle<U> {
+        let dispatch = self.dispatched.get() + 1;
+        self.dispatched.set(dispatch);
+        *self.pending.borrow_mut() = Some((dispatch, msg));
+
+        let universe = self.universe.clone();
+        let pending = self.pending.clone();
+        self.scheduler.schedule(
+            self.delay,
+            Box::new(move || {
+                // Delays that were restarted end without a message, it's left to the last one.
+                let message = {
+                    let mut pending = pending.borrow_mut();
+                    match pending.take() {
+                        Some((latest, msg)) if latest == dispatch => Some(msg),
+                        other => {
+                            *pending = other;
+                            None
+                        }
+                    }
+                };
+
+                if let (Some(universe), Some(msg)) = (universe.upgrade(), message) {
+                    universe.msg(msg);
+                }
+            }),
+        );
+
+        DebounceHandle {
+            pending: self.pending.clone(),
+            dispatch,
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Creates a `Debouncer` that applies its messages to this universe after `delay`, timed by
+    /// `scheduler`.
+    ///
+    /// The debouncer doesn't keep the universe alive. Messages whose delay ends after the universe
+    /// was dropped are discarded.
+    pub fn debouncer(&self, delay: Duration, scheduler: impl Scheduler + 'static) -> Debouncer<U> {
+        Debouncer {
+            universe: self.downgrade(),
+            delay,
+            scheduler: Rc::new(scheduler),
+            pending: Rc::new(RefCell::new(None)),
+            dispatched: Cell::new(0),
+        }
+    }
+}
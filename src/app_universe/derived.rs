@@ -0,0 +1,103 @@
+use super::{AppUniverse, AppUniverseCore, SubscriptionGuard, SubscriptionId};
+use std::{
+    any::Any,
+    cell::{Cell, Ref, RefCell},
+    rc::Rc,
+};
+
+type DerivedSubscriberFn<V> = Box<dyn FnMut(&V)>;
+type DerivedSubscribers<V> = Rc<RefCell<Vec<Rc<DerivedSubscription<V>>>>>;
+
+struct DerivedSubscription<V> {
+    id: SubscriptionId,
+    callback: RefCell<DerivedSubscriberFn<V>>,
+    // Set when it's removed, so a notification that already collected it skips it.
+    unsubscribed: Cell<bool>,
+}
+
+/// A `DerivedUniverse` is a read-only projection of another universe, created with
+/// `AppUniverse::derived`.
+///
+/// It's push-based: the projection is recomputed eagerly every time the source universe notifies
+/// its subscribers, whether or not anything reads it, and then its own subscribers are notified.
+/// It can't be dispatched to. Dropping it removes its subscription from the source universe.
+pub struct DerivedUniverse<V> {
+    value: Rc<RefCell<V>>,
+    subscribers: DerivedSubscribers<V>,
+    next_subscription_id: Cell<u64>,
+    // The `SubscriptionGuard` for the source, boxed so the source doesn't show up in the type.
+    _guard: Box<dyn Any>,
+}
+
+impl<V> DerivedUniverse<V> {
+    /// Acquire read access to the projection.
+    pub fn read(&self) -> Ref<'_, V> {
+        self.value.borrow()
+    }
+
+    /// This function takes a subscriber function that runs with the new projection anytime it has
+    /// been recomputed.
+    pub fn subscribe(&mut self, subscriber_fn: impl FnMut(&V) + 'static) -> SubscriptionId {
+        let id = SubscriptionId(self.next_subscription_id.get());
+        self.next_subscription_id.set(id.0 + 1);
+
+        self.subscribers
+            .borrow_mut()
+            .push(Rc::new(DerivedSubscription {
+                id,
+                callback: RefCell::new(Box::new(subscriber_fn)),
+                unsubscribed: Cell::new(false),
+            }));
+        id
+    }
+
+    /// This function removes the subscription with `id` and returns whether it was still
+    /// registered.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let mut subscribers = self.subscribers.borrow_mut();
+
+        match subscribers
+            .iter()
+            .position(|subscription| subscription.id == id)
+        {
+            Some(index) => {
+                subscribers.remove(index).unsubscribed.set(true);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Creates a `DerivedUniverse` holding `f` applied to the state, which is kept up to date by a
+    /// subscription to this universe.
+    pub fn derived<V: 'static>(&self, f: impl Fn(&U) -> V + 'static) -> DerivedUniverse<V> {
+        let value = Rc::new(RefCell::new(f(&self.read())));
+        let subscribers: DerivedSubscribers<V> = Rc::new(RefCell::new(vec![]));
+
+        let derived_value = value.clone();
+        let derived_subscribers = subscribers.clone();
+        let subscription = self.clone().subscribe(move |universe| {
+            let recomputed = f(&universe.read());
+            *derived_value.borrow_mut() = recomputed;
+
+            // The subscribers are collected first, so they can subscribe and unsubscribe while
+            // being notified. Subscriptions made during the pass wait for the next one.
+            let subscribers = derived_subscribers.borrow().clone();
+            let value = derived_value.borrow();
+            for subscription in subscribers {
+                if !subscription.unsubscribed.get() {
+                    (subscription.callback.borrow_mut())(&value);
+                }
+            }
+        });
+
+        DerivedUniverse {
+            value,
+            subscribers,
+            next_subscription_id: Cell::new(0),
+            _guard: Box::new(SubscriptionGuard::new(self, subscription)),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use super::{AppUniverse, AppUniverseCore, SubscriberPriority, SubscriptionId};
+
+/// A `SubscriptionInfo` describes a registered subscription, for devtools that list subscribers.
+///
+/// It's a snapshot taken by `subscriptions_info` and doesn't change afterwards. The existing fields
+/// keep their meaning across minor versions, but fields may be added, so it can't be constructed or
+/// matched exhaustively outside of this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub struct SubscriptionInfo {
+    /// The id of the subscription.
+    pub id: SubscriptionId,
+    /// The label of the subscription, if it was given one.
+    pub label: Option<String>,
+    /// The priority the subscription is notified with.
+    pub priority: SubscriberPriority,
+    /// The version of the state when the subscription was registered.
+    pub created_version: u64,
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Describes every registered subscription, in the order they are notified.
+    ///
+    /// This is only available with the `devtools` or `test-utils` features, so production builds
+    /// don't keep the metadata around.
+    pub fn subscriptions_info(&self) -> Vec<SubscriptionInfo> {
+        self.inner
+            .subscriptions
+            .borrow()
+            .iter()
+            .map(|subscription| {
+                let subscription = subscription.borrow();
+                SubscriptionInfo {
+                    id: subscription.id,
+                    label: subscription.label.clone(),
+                    priority: subscription.priority.get(),
+                    created_version: subscription.created_version,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,282 @@
+use super::{notification::Notify, AppUniverseCore, Dispatcher};
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+};
+
+/// A `DispatchId` identifies a dispatch chain started with `AppUniverse::msg_traced`.
+///
+/// Every message that is dispatched while the chain is being processed (from subscribers or from
+/// async dispatches started during the chain) carries the same id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DispatchId(u64);
+
+impl fmt::Display for DispatchId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "dispatch-{}", self.0)
+    }
+}
+
+/// The `DispatchPriority` decides which lane of the dispatch queue a dispatch waits in.
+///
+/// Lanes only matter while dispatches are queued, i.e. for messages sent while another dispatch is
+/// being processed (from subscribers, async dispatches or senders). The queue drains `High` before
+/// `Normal` before `Low`, and dispatches within a lane stay in the order they were queued.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DispatchPriority {
+    /// Latency-critical messages, e.g. echoing user input.
+    High,
+    /// The priority of every dispatch that doesn't ask for another one.
+    #[default]
+    Normal,
+    /// Bulk messages, e.g. background sync results.
+    Low,
+}
+
+// How many higher-priority dispatches may be drained while `Low` dispatches wait, unless the
+// universe sets another limit.
+const DEFAULT_STARVATION_LIMIT: u32 = 8;
+
+// How deep dispatches may be nested, i.e. queued from a subscriber notified for a dispatch that was
+// itself queued from a subscriber, unless the universe sets another limit.
+const DEFAULT_DEPTH_LIMIT: u32 = 100;
+
+// Applies messages that it produces from the state as it goes and returns how many it applied.
+pub(crate) type Producer<U> = Box<dyn FnOnce(&mut U, &Dispatcher<U>) -> u64>;
+
+// A group of messages that is applied with a single notification.
+pub(crate) struct QueuedDispatch<U: AppUniverseCore> {
+    pub(crate) messages: Vec<U::Message>,
+    pub(crate) producer: Option<Producer<U>>,
+    pub(crate) id: Option<DispatchId>,
+    pub(crate) notify: Notify,
+    pub(crate) priority: DispatchPriority,
+    // How many dispatches this one was queued from, set when it's pushed.
+    depth: u32,
+}
+
+impl<U: AppUniverseCore> QueuedDispatch<U> {
+    pub(crate) fn new(messages: Vec<U::Message>, id: Option<DispatchId>) -> Self {
+        QueuedDispatch {
+            messages,
+            producer: None,
+            id,
+            notify: Notify::IfChanged,
+            priority: DispatchPriority::Normal,
+            depth: 0,
+        }
+    }
+
+    // A dispatch that doesn't change the core but still notifies subscribers.
+    #[cfg(feature = "async")]
+    pub(crate) fn notification(id: Option<DispatchId>) -> Self {
+        QueuedDispatch {
+            messages: vec![],
+            producer: None,
+            id,
+            notify: Notify::Always,
+            priority: DispatchPriority::Normal,
+            depth: 0,
+        }
+    }
+
+    // A dispatch that runs a pending deferred notification.
+    pub(crate) fn flush() -> Self {
+        QueuedDispatch {
+            messages: vec![],
+            producer: None,
+            id: None,
+            notify: Notify::Flush,
+            priority: DispatchPriority::Normal,
+            depth: 0,
+        }
+    }
+
+    // A dispatch whose messages are produced from the state while it is applied.
+    pub(crate) fn produced(producer: Producer<U>, id: Option<DispatchId>) -> Self {
+        QueuedDispatch {
+            messages: vec![],
+            producer: Some(producer),
+            id,
+            notify: Notify::IfChanged,
+            priority: DispatchPriority::Normal,
+            depth: 0,
+        }
+    }
+
+    pub(crate) fn with_priority(mut self, priority: DispatchPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub(crate) fn with_notify(mut self, notify: Notify) -> Self {
+        self.notify = notify;
+        self
+    }
+}
+
+/// The queue every dispatch goes through. Messages dispatched while the queue is being drained
+/// (e.g. from inside a subscriber) are applied after the current notification pass instead of
+/// re-entering it.
+///
+/// There is one lane per `DispatchPriority`. Once `starvation_limit` higher-priority dispatches
+/// have been drained while a `Low` dispatch was waiting, that `Low` dispatch goes next.
+///
+/// Dispatches pushed while another one is applied are nested one level deeper. Pushing one deeper
+/// than `depth_limit` is rejected, which catches subscribers that keep dispatching to each other.
+pub(crate) struct DispatchQueue<U: AppUniverseCore> {
+    lanes: RefCell<[VecDeque<QueuedDispatch<U>>; 3]>,
+    starvation_limit: Cell<u32>,
+    low_skipped: Cell<u32>,
+    depth_limit: Cell<u32>,
+    current_depth: Cell<u32>,
+    applied: Cell<u64>,
+    draining: Cell<bool>,
+    current_id: Cell<Option<DispatchId>>,
+    next_id: Cell<u64>,
+}
+
+impl<U: AppUniverseCore> DispatchQueue<U> {
+    pub(crate) fn new() -> Self {
+        DispatchQueue {
+            lanes: RefCell::new([VecDeque::new(), VecDeque::new(), VecDeque::new()]),
+            starvation_limit: Cell::new(DEFAULT_STARVATION_LIMIT),
+            low_skipped: Cell::new(0),
+            depth_limit: Cell::new(DEFAULT_DEPTH_LIMIT),
+            current_depth: Cell::new(0),
+            applied: Cell::new(0),
+            draining: Cell::new(false),
+            current_id: Cell::new(None),
+            next_id: Cell::new(0),
+        }
+    }
+
+    pub(crate) fn next_id(&self) -> DispatchId {
+        let id = self.next_id.get() + 1;
+        self.next_id.set(id);
+        DispatchId(id)
+    }
+
+    pub(crate) fn current_id(&self) -> Option<DispatchId> {
+        self.current_id.get()
+    }
+
+    pub(crate) fn is_draining(&self) -> bool {
+        self.draining.get()
+    }
+
+    pub(crate) fn set_starvation_limit(&self, limit: u32) {
+        self.starvation_limit.set(limit);
+    }
+
+    // Counts a dispatch that applied at least one message.
+    pub(crate) fn record_applied(&self) {
+        self.applied.set(self.applied.get() + 1);
+    }
+
+    pub(crate) fn applied_count(&self) -> u64 {
+        self.applied.get()
+    }
+
+    pub(crate) fn set_depth_limit(&self, limit: u32) {
+        self.depth_limit.set(limit);
+    }
+
+    // Returns `true` if a dispatch pushed now would be nested deeper than the depth limit.
+    pub(crate) fn exceeds_depth_limit(&self) -> bool {
+        self.draining.get() && self.current_depth.get() + 1 > self.depth_limit.get()
+    }
+
+    pub(crate) fn push(&self, mut dispatch: QueuedDispatch<U>) {
+        if self.exceeds_depth_limit() {
+            reject_nested_dispatch(self.depth_limit.get());
+            return;
+        }
+        if self.draining.get() {
+            dispatch.depth = self.current_depth.get() + 1;
+        }
+
+        self.lanes.borrow_mut()[dispatch.priority as usize].push_back(dispatch);
+    }
+
+    // Takes the next dispatch from the highest non-empty lane, unless a waiting `Low` dispatch
+    // has been skipped too often.
+    fn pop_next(&self) -> Option<QueuedDispatch<U>> {
+        let low = DispatchPriority::Low as usize;
+        let mut lanes = self.lanes.borrow_mut();
+        let low_waiting = !lanes[low].is_empty();
+
+        if low_waiting && self.low_skipped.get() >= self.starvation_limit.get() {
+            self.low_skipped.set(0);
+            return lanes[low].pop_front();
+        }
+
+        let dispatch = lanes.iter_mut().find_map(|lane| lane.pop_front())?;
+
+        if dispatch.priority == DispatchPriority::Low {
+            self.low_skipped.set(0);
+        } else if low_waiting {
+            self.low_skipped.set(self.low_skipped.get() + 1);
+        }
+
+        Some(dispatch)
+    }
+
+    /// Applies `apply` to every queued dispatch until the queue is empty.
+    ///
+    /// If the queue is already being drained further up the stack this returns straight away and
+    /// the outer drain picks up whatever was pushed.
+    pub(crate) fn drain(&self, mut apply: impl FnMut(QueuedDispatch<U>)) {
+        if self.draining.replace(true) {
+            return;
+        }
+
+        let _guard = DrainGuard(self);
+
+        loop {
+            let dispatch = match self.pop_next() {
+                Some(dispatch) => dispatch,
+                None => break,
+            };
+
+            let previous_id = self.current_id.replace(dispatch.id);
+            self.current_depth.set(dispatch.depth);
+            apply(dispatch);
+            self.current_id.set(previous_id);
+        }
+    }
+}
+
+// Called for a dispatch nested deeper than the depth limit through anything but `try_msg`, which
+// most likely comes from subscribers that dispatch in an endless loop.
+fn reject_nested_dispatch(limit: u32) {
+    if cfg!(debug_assertions) {
+        panic!("Dispatches nested deeper than the limit of {}", limit);
+    }
+
+    log::error!(
+        "Dropped a dispatch nested deeper than the limit of {}",
+        limit
+    );
+}
+
+// Resets the queue if a reducer or subscriber panics halfway through a drain, so the universe
+// stays usable for callers that catch the panic.
+struct DrainGuard<'a, U: AppUniverseCore>(&'a DispatchQueue<U>);
+
+impl<U: AppUniverseCore> Drop for DrainGuard<'_, U> {
+    fn drop(&mut self) {
+        self.0.draining.set(false);
+        self.0.current_id.set(None);
+        self.0.current_depth.set(0);
+        if std::thread::panicking() {
+            self.0
+                .lanes
+                .borrow_mut()
+                .iter_mut()
+                .for_each(VecDeque::clear);
+            self.0.low_skipped.set(0);
+        }
+    }
+}
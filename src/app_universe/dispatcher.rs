@@ -0,0 +1,38 @@
+use super::AppUniverseCore;
+use std::cell::RefCell;
+
+/// A `Dispatcher` is passed to `AppUniverseCore::msg_with`, so a core can dispatch follow-up
+/// messages while it handles one, without holding on to the universe.
+pub struct Dispatcher<U: AppUniverseCore> {
+    follow_ups: RefCell<Vec<U::Message>>,
+}
+
+impl<U: AppUniverseCore> Dispatcher<U> {
+    /// Creates a dispatcher that isn't connected to a universe, e.g. to call `msg_with` directly
+    /// and inspect its follow-ups with `into_follow_ups`.
+    pub fn new() -> Self {
+        Dispatcher {
+            follow_ups: RefCell::new(vec![]),
+        }
+    }
+
+    /// Dispatches `msg` once the dispatch that is being applied is complete.
+    ///
+    /// Follow-ups are never applied re-entrantly. They are queued together as one dispatch of the
+    /// same dispatch chain and with the same priority, before subscribers are notified of the
+    /// current one, so they are applied ahead of anything those subscribers dispatch.
+    pub fn dispatch(&self, msg: U::Message) {
+        self.follow_ups.borrow_mut().push(msg);
+    }
+
+    /// Returns the follow-up messages dispatched so far, in order.
+    pub fn into_follow_ups(self) -> Vec<U::Message> {
+        self.follow_ups.into_inner()
+    }
+}
+
+impl<U: AppUniverseCore> Default for Dispatcher<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
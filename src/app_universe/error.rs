@@ -0,0 +1,37 @@
+use std::fmt;
+
+/// A `UniverseError` is returned by the fallible methods of `AppUniverse`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum UniverseError {
+    /// The subscription was already removed, or belongs to another universe.
+    SubscriptionNotFound,
+    /// `dispatch_coalesced` was called before `set_coalescing_window`.
+    NoScheduler,
+    /// An async method was called without a spawner, see `set_spawner`.
+    NoSpawner,
+    /// `recover_by_replay` was called without `enable_replay_recovery`, or after recording stopped.
+    ReplayRecoveryDisabled,
+    /// The state is borrowed, e.g. by a `Ref` returned from `read` that is still alive.
+    AlreadyBorrowed,
+    /// The universe is frozen, see `freeze`.
+    Frozen,
+    /// The dispatch would be nested deeper than the limit, see `set_dispatch_depth_limit`.
+    DepthLimitExceeded,
+}
+
+impl fmt::Display for UniverseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UniverseError::SubscriptionNotFound => "Subscription not found",
+            UniverseError::NoScheduler => "No scheduler has been set",
+            UniverseError::NoSpawner => "No spawner has been set",
+            UniverseError::ReplayRecoveryDisabled => "Replay recovery isn't enabled",
+            UniverseError::AlreadyBorrowed => "The state is already borrowed",
+            UniverseError::Frozen => "The universe is frozen",
+            UniverseError::DepthLimitExceeded => "Dispatches are nested deeper than the limit",
+        })
+    }
+}
+
+impl std::error::Error for UniverseError {}
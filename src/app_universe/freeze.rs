@@ -0,0 +1,33 @@
+use super::{AppUniverse, AppUniverseCore};
+
+// Called for a dispatch that reached a frozen universe through anything but `try_msg`.
+pub(crate) fn reject_frozen_dispatch() {
+    if cfg!(debug_assertions) {
+        panic!("Dispatched while the universe is frozen");
+    }
+
+    log::error!("Dropped a message dispatched while the universe is frozen");
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Freezes the universe until `thaw` is called, e.g. for the duration of a render.
+    ///
+    /// Dispatching to a frozen universe is a bug: `try_msg` returns `Err(UniverseError::Frozen)`,
+    /// while `msg` and every other way of dispatching panic in debug builds and drop the message in
+    /// release builds.
+    /// Unlike `close` this is temporary, and unlike a paused notification the state can't change.
+    /// Messages that were already queued when the universe was frozen are still applied.
+    pub fn freeze(&self) {
+        self.inner.frozen.set(true);
+    }
+
+    /// Lets messages be dispatched again after `freeze`.
+    pub fn thaw(&self) {
+        self.inner.frozen.set(false);
+    }
+
+    /// Returns `true` if the universe is frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.inner.frozen.get()
+    }
+}
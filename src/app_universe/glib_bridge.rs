@@ -0,0 +1,128 @@
+//! Integration with the GLib main loop that GTK (and relm4) applications run on.
+//!
+//! Widgets may only be touched from the main context, while messages often come from worker
+//! threads. A `GlibUniverseBridge` receives messages through a `Send` sender and dispatches them
+//! on the main context, and `subscribe_on_main` defers subscribers to an idle callback there.
+
+use super::{
+    dispatch::QueuedDispatch, AppUniverse, AppUniverseCore, DispatchPriority, UniverseSubscription,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+/// A `Send` handle that forwards messages to a universe attached to a GLib main context.
+/// It's obtained from `GlibUniverseBridge::sender` and can be cloned and moved to other threads.
+pub struct UniverseSender<M>(async_channel::Sender<(M, DispatchPriority)>);
+
+impl<M> UniverseSender<M> {
+    /// Sends `message` to be dispatched on the main context.
+    ///
+    /// The message is handed back if the bridge it belongs to has been dropped.
+    pub fn send(&self, message: M) -> Result<(), M> {
+        self.send_with_priority(message, DispatchPriority::Normal)
+    }
+
+    /// Sends `message` like `send`, with the given dispatch priority.
+    ///
+    /// All messages that arrived by the time the main context gets to them are queued together,
+    /// so they are dispatched in the order of their priorities.
+    pub fn send_with_priority(&self, message: M, priority: DispatchPriority) -> Result<(), M> {
+        self.0
+            .try_send((message, priority))
+            .map_err(|error| error.into_inner().0)
+    }
+}
+
+impl<M> Clone for UniverseSender<M> {
+    fn clone(&self) -> Self {
+        UniverseSender(self.0.clone())
+    }
+}
+
+/// The `GlibUniverseBridge` dispatches messages received from a `UniverseSender` on a GLib main
+/// context. Dropping the bridge detaches it from the main context, after which sending fails.
+pub struct GlibUniverseBridge<U: AppUniverseCore> {
+    universe: AppUniverse<U>,
+    main_context: glib::MainContext,
+    sender: UniverseSender<U::Message>,
+    receiver_task: glib::JoinHandle<()>,
+}
+
+impl<U> GlibUniverseBridge<U>
+where
+    U: AppUniverseCore + 'static,
+    U::Message: Send + 'static,
+{
+    /// Attaches `universe` to `main_context`.
+    ///
+    /// This has to be called from the thread that owns `main_context`, e.g. from inside the main
+    /// loop or within `MainContext::with_thread_default`.
+    pub fn attach(universe: AppUniverse<U>, main_context: &glib::MainContext) -> Self {
+        let (sender, receiver) = async_channel::unbounded();
+
+        let dispatching_universe = universe.clone();
+        let receiver_task = main_context.spawn_local(async move {
+            while let Ok(first) = receiver.recv().await {
+                let arrived =
+                    std::iter::once(first).chain(std::iter::from_fn(|| receiver.try_recv().ok()));
+                let id = dispatching_universe.current_dispatch_id();
+
+                dispatching_universe.enqueue_all(arrived.map(|(message, priority)| {
+                    QueuedDispatch::new(vec![message], id).with_priority(priority)
+                }));
+            }
+        });
+
+        GlibUniverseBridge {
+            universe,
+            main_context: main_context.clone(),
+            sender: UniverseSender(sender),
+            receiver_task,
+        }
+    }
+
+    /// Returns a sender that can be used to dispatch messages from any thread.
+    pub fn sender(&self) -> UniverseSender<U::Message> {
+        self.sender.clone()
+    }
+
+    /// Returns the universe attached to this bridge.
+    pub fn universe(&self) -> &AppUniverse<U> {
+        &self.universe
+    }
+
+    /// This function takes a subscriber function that runs as an idle callback on the main context
+    /// whenever state changes.
+    ///
+    /// Notifications that happen before the idle callback had a chance to run are coalesced, so the
+    /// subscriber runs once with the latest state.
+    pub fn subscribe_on_main(
+        &mut self,
+        subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        let main_context = self.main_context.clone();
+        let scheduled = Rc::new(Cell::new(false));
+        let subscriber_fn = Rc::new(RefCell::new(subscriber_fn));
+
+        self.universe.subscribe(move |universe| {
+            if scheduled.replace(true) {
+                return;
+            }
+
+            let scheduled = scheduled.clone();
+            let subscriber_fn = subscriber_fn.clone();
+            main_context.spawn_local_with_priority(glib::Priority::DEFAULT_IDLE, async move {
+                scheduled.set(false);
+                (subscriber_fn.borrow_mut())(universe);
+            });
+        })
+    }
+}
+
+impl<U: AppUniverseCore> Drop for GlibUniverseBridge<U> {
+    fn drop(&mut self) {
+        self.receiver_task.abort();
+    }
+}
@@ -0,0 +1,108 @@
+use super::{AppUniverse, AppUniverseCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt;
+
+// Bumped whenever the shape of the payload produced by `dehydrate` changes.
+const HYDRATION_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct DehydratedRef<'a, U> {
+    format: u32,
+    state: &'a U,
+}
+
+#[derive(Deserialize)]
+struct Dehydrated<U> {
+    format: u32,
+    state: U,
+}
+
+/// The error returned when a universe can't be hydrated from a payload.
+#[derive(Debug)]
+pub enum HydrateError {
+    /// The payload isn't valid JSON or doesn't match the shape of the state.
+    Malformed(serde_json::Error),
+    /// The payload was produced by an incompatible version of `app-universe`.
+    IncompatibleVersion {
+        /// The format version found in the payload.
+        found: u32,
+        /// The format version this build understands.
+        expected: u32,
+    },
+}
+
+impl fmt::Display for HydrateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HydrateError::Malformed(error) => write!(f, "Malformed hydration payload: {}", error),
+            HydrateError::IncompatibleVersion { found, expected } => write!(
+                f,
+                "Hydration payload has format version {} but {} was expected",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HydrateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HydrateError::Malformed(error) => Some(error),
+            HydrateError::IncompatibleVersion { .. } => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for HydrateError {
+    fn from(error: serde_json::Error) -> Self {
+        HydrateError::Malformed(error)
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Serializes the state into a compact JSON payload that a client can `hydrate` from.
+    ///
+    /// Every `<` is escaped, so the payload can be embedded in a `<script>` tag as is.
+    pub fn dehydrate(&self) -> Result<String, serde_json::Error>
+    where
+        U: Serialize,
+    {
+        let payload = serde_json::to_string(&DehydratedRef {
+            format: HYDRATION_FORMAT_VERSION,
+            state: &*self.read(),
+        })?;
+
+        Ok(payload.replace('<', "\\u003c"))
+    }
+
+    /// Creates a universe from a payload produced by `dehydrate`.
+    ///
+    /// There are no subscribers at this point, so no notification happens.
+    pub fn hydrate(payload: &str) -> Result<AppUniverse<U>, HydrateError>
+    where
+        U: DeserializeOwned,
+    {
+        let dehydrated: Dehydrated<serde_json::Value> = serde_json::from_str(payload)?;
+
+        if dehydrated.format != HYDRATION_FORMAT_VERSION {
+            return Err(HydrateError::IncompatibleVersion {
+                found: dehydrated.format,
+                expected: HYDRATION_FORMAT_VERSION,
+            });
+        }
+
+        Ok(AppUniverse::new(serde_json::from_value(dehydrated.state)?))
+    }
+
+    /// Creates a universe from a payload produced by `dehydrate`, falling back to `core_fallback`
+    /// when the payload is missing or can't be hydrated.
+    pub fn hydrate_into(core_fallback: U, payload: Option<&str>) -> AppUniverse<U>
+    where
+        U: DeserializeOwned,
+    {
+        match payload.map(AppUniverse::hydrate) {
+            Some(Ok(universe)) => universe,
+            _ => AppUniverse::new(core_fallback),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Integration with [Iced](https://iced.rs), where the universe is the single source of truth.
+//!
+//! Iced's `update` forwards messages into the universe with `forward_to_universe`, and the
+//! subscription returned by `AppUniverse::iced_subscription` turns notifications into Iced messages
+//! carrying the new version, so Iced knows it has to redraw.
+//!
+//! ```rust,ignore
+//! use app_universe::{forward_to_universe, AppUniverse};
+//! use iced::{widget::text, Element, Subscription, Task};
+//!
+//! struct App {
+//!     universe: AppUniverse<Counter>,
+//! }
+//!
+//! #[derive(Debug, Clone)]
+//! enum Event {
+//!     Increment,
+//!     UniverseChanged(u64),
+//! }
+//!
+//! impl App {
+//!     fn update(&mut self, event: Event) -> Task<Event> {
+//!         match event {
+//!             Event::Increment => forward_to_universe(&self.universe, Msg::Increment(1)),
+//!             // Nothing to do, Iced redraws after every update.
+//!             Event::UniverseChanged(_) => Task::none(),
+//!         }
+//!     }
+//!
+//!     fn view(&self) -> Element<Event> {
+//!         // Copy what the view needs out of the state, so the borrow ends before the
+//!         // widgets are returned and is never held across the frame.
+//!         let counter = self.universe.read().counter;
+//!         text(counter).into()
+//!     }
+//!
+//!     fn subscription(&self) -> Subscription<Event> {
+//!         self.universe.iced_subscription().map(Event::UniverseChanged)
+//!     }
+//! }
+//! ```
+
+use super::{AppUniverse, AppUniverseCore};
+use futures::{channel::mpsc, StreamExt};
+use iced_futures::subscription::{self, EventStream, Hasher, Recipe};
+use iced_futures::BoxStream;
+use std::{hash::Hash, rc::Rc};
+
+/// The `UniverseSubscriptionRecipe` produces the version of the universe every time its
+/// subscribers are notified.
+///
+/// Recipes for the same universe hash the same, so Iced keeps a single bridge subscription per
+/// universe no matter how often `subscription` is called. Once Iced drops the stream, the bridge
+/// subscriber removes itself the next time the universe notifies.
+pub struct UniverseSubscriptionRecipe<U: AppUniverseCore> {
+    universe: AppUniverse<U>,
+}
+
+impl<U: AppUniverseCore + 'static> UniverseSubscriptionRecipe<U> {
+    /// Creates a recipe for `universe`.
+    pub fn new(universe: &AppUniverse<U>) -> Self {
+        UniverseSubscriptionRecipe {
+            universe: universe.clone(),
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> Recipe for UniverseSubscriptionRecipe<U> {
+    type Output = u64;
+
+    fn hash(&self, state: &mut Hasher) {
+        std::any::TypeId::of::<Self>().hash(state);
+        (Rc::as_ptr(&self.universe.inner.universe) as usize).hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: EventStream) -> BoxStream<Self::Output> {
+        let (sender, receiver) = mpsc::unbounded();
+        let mut universe = self.universe;
+
+        let _ = universe.subscribe_with_ctx(Box::new(move |ctx| {
+            if sender.is_closed() {
+                ctx.unsubscribe_self();
+            } else {
+                let _ = sender.unbounded_send(ctx.batch().end_version);
+            }
+        }));
+
+        receiver.boxed()
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Returns an Iced subscription that yields the new version whenever state changes.
+    pub fn iced_subscription(&self) -> subscription::Subscription<u64> {
+        subscription::from_recipe(UniverseSubscriptionRecipe::new(self))
+    }
+}
+
+/// Dispatches `message` to `universe` from Iced's `update` and returns a task that does nothing,
+/// since the redraw is driven by the subscription.
+pub fn forward_to_universe<U, T>(
+    universe: &AppUniverse<U>,
+    message: U::Message,
+) -> iced_runtime::Task<T>
+where
+    U: AppUniverseCore + 'static,
+{
+    universe.msg(message);
+    iced_runtime::Task::none()
+}
@@ -0,0 +1,217 @@
+//! An `AsyncStorage` backed by IndexedDB, for states that outgrow `localStorage`.
+
+use super::{AsyncStorage, StorageError, StorageFuture};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{DomException, IdbDatabase, IdbFactory, IdbRequest, IdbTransaction};
+
+/// The `IndexedDbStorage` saves the snapshot under a single key of an object store.
+///
+/// The database is opened on first use and created or upgraded as needed, so several storages can
+/// share a database with a store each. It is closed again when
+/// another page wants to upgrade it, and reopened on the next save or load.
+pub struct IndexedDbStorage {
+    database_name: String,
+    store_name: String,
+    key: String,
+    database: Rc<RefCell<Option<IdbDatabase>>>,
+}
+
+impl IndexedDbStorage {
+    /// Creates a storage that saves the snapshot under `key` in the `store_name` object store of
+    /// the `database_name` database.
+    pub fn new(
+        database_name: impl Into<String>,
+        store_name: impl Into<String>,
+        key: impl Into<String>,
+    ) -> Self {
+        IndexedDbStorage {
+            database_name: database_name.into(),
+            store_name: store_name.into(),
+            key: key.into(),
+            database: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    fn handle(&self) -> DatabaseHandle {
+        DatabaseHandle {
+            database_name: self.database_name.clone(),
+            store_name: self.store_name.clone(),
+            key: self.key.clone(),
+            database: self.database.clone(),
+        }
+    }
+}
+
+impl AsyncStorage for IndexedDbStorage {
+    fn save(&self, snapshot: String) -> StorageFuture<()> {
+        let handle = self.handle();
+
+        Box::pin(async move {
+            let database = handle.open().await?;
+            let transaction = database
+                .transaction_with_str_and_mode(
+                    &handle.store_name,
+                    web_sys::IdbTransactionMode::Readwrite,
+                )
+                .map_err(storage_error)?;
+
+            transaction
+                .object_store(&handle.store_name)
+                .and_then(|store| {
+                    store.put_with_key(
+                        &JsValue::from_str(&snapshot),
+                        &JsValue::from_str(&handle.key),
+                    )
+                })
+                .map_err(storage_error)?;
+
+            // Quota errors abort the transaction, so the save only succeeded once it completes.
+            transaction_completed(&transaction)
+                .await
+                .map_err(storage_error)?;
+            Ok(())
+        })
+    }
+
+    fn load(&self) -> StorageFuture<Option<String>> {
+        let handle = self.handle();
+
+        Box::pin(async move {
+            let database = handle.open().await?;
+            let request = database
+                .transaction_with_str(&handle.store_name)
+                .and_then(|transaction| transaction.object_store(&handle.store_name))
+                .and_then(|store| store.get(&JsValue::from_str(&handle.key)))
+                .map_err(storage_error)?;
+
+            let snapshot = request_completed(&request).await.map_err(storage_error)?;
+            Ok(snapshot.as_string())
+        })
+    }
+}
+
+// Everything a save or load needs, so their futures don't borrow the storage.
+struct DatabaseHandle {
+    database_name: String,
+    store_name: String,
+    key: String,
+    database: Rc<RefCell<Option<IdbDatabase>>>,
+}
+
+impl DatabaseHandle {
+    async fn open(&self) -> Result<IdbDatabase, StorageError> {
+        if let Some(database) = self.database.borrow().as_ref() {
+            return Ok(database.clone());
+        }
+
+        let factory = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("indexedDB"))
+            .ok()
+            .and_then(|factory| factory.dyn_into::<IdbFactory>().ok())
+            .ok_or_else(|| StorageError::Backend(String::from("IndexedDB is not available")))?;
+
+        // Open whatever version exists, which creates the database with the store if it's new.
+        let mut database = self.open_version(&factory, None).await?;
+        if !database.object_store_names().contains(&self.store_name) {
+            // The database was created without this store, e.g. by a storage with another store,
+            // and stores can only be created while upgrading.
+            let version = database.version() + 1.0;
+            database.close();
+            database = self.open_version(&factory, Some(version)).await?;
+        }
+
+        // Let other pages upgrade the database, it's reopened the next time it's needed.
+        let cached = self.database.clone();
+        let closing = database.clone();
+        let on_version_change = Closure::<dyn FnMut()>::new(move || {
+            closing.close();
+            cached.borrow_mut().take();
+        });
+        database.set_onversionchange(Some(on_version_change.as_ref().unchecked_ref()));
+        on_version_change.forget();
+
+        *self.database.borrow_mut() = Some(database.clone());
+        Ok(database)
+    }
+
+    // Opens `version` of the database, or the current one, creating the store if it upgrades.
+    async fn open_version(
+        &self,
+        factory: &IdbFactory,
+        version: Option<f64>,
+    ) -> Result<IdbDatabase, StorageError> {
+        let request = match version {
+            Some(version) => factory.open_with_f64(&self.database_name, version),
+            None => factory.open(&self.database_name),
+        }
+        .map_err(storage_error)?;
+
+        let store_name = self.store_name.clone();
+        let upgraded = request.clone();
+        let on_upgrade_needed = Closure::once_into_js(move || {
+            if let Ok(database) = upgraded.result() {
+                let database: IdbDatabase = database.unchecked_into();
+                if !database.object_store_names().contains(&store_name) {
+                    let _ = database.create_object_store(&store_name);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.unchecked_ref()));
+
+        Ok(request_completed(&request)
+            .await
+            .map_err(storage_error)?
+            .unchecked_into())
+    }
+}
+
+// Resolves with the result of `request` once it succeeded.
+fn request_completed(request: &IdbRequest) -> JsFuture {
+    let request = request.clone();
+
+    JsFuture::from(js_sys::Promise::new(&mut |resolve, reject| {
+        let succeeded = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let result = succeeded.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::UNDEFINED, &result);
+        });
+
+        let failed = request.clone();
+        let on_error = Closure::once_into_js(move || {
+            let error = failed.error().ok().flatten().map(JsValue::from);
+            let _ = reject.call1(&JsValue::UNDEFINED, &error.unwrap_or(JsValue::UNDEFINED));
+        });
+
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    }))
+}
+
+// Resolves once `transaction` committed and rejects if it was aborted.
+fn transaction_completed(transaction: &IdbTransaction) -> JsFuture {
+    let transaction = transaction.clone();
+
+    JsFuture::from(js_sys::Promise::new(&mut |resolve, reject| {
+        let on_complete = Closure::once_into_js(move || {
+            let _ = resolve.call0(&JsValue::UNDEFINED);
+        });
+
+        let aborted = transaction.clone();
+        let on_abort = Closure::once_into_js(move || {
+            let error = aborted.error().map(JsValue::from);
+            let _ = reject.call1(&JsValue::UNDEFINED, &error.unwrap_or(JsValue::UNDEFINED));
+        });
+
+        transaction.set_oncomplete(Some(on_complete.unchecked_ref()));
+        transaction.set_onabort(Some(on_abort.unchecked_ref()));
+    }))
+}
+
+fn storage_error(error: JsValue) -> StorageError {
+    match error.dyn_ref::<DomException>() {
+        Some(exception) if exception.name() == "QuotaExceededError" => StorageError::QuotaExceeded,
+        Some(exception) => StorageError::Backend(exception.message()),
+        None => StorageError::Backend(format!("{:?}", error)),
+    }
+}
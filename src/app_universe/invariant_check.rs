@@ -0,0 +1,85 @@
+use super::{AppUniverse, AppUniverseCore};
+use std::{
+    cell::Cell,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+type ViolationHandler = Box<dyn Fn(u64)>;
+
+// Remembers the hash of the state as `msg` left it, so changes made any other way can be noticed.
+pub(crate) struct InvariantCheck<U> {
+    hash: Box<dyn Fn(&U) -> u64>,
+    expected: Cell<u64>,
+    on_violation: ViolationHandler,
+}
+
+impl<U> InvariantCheck<U> {
+    // Called whenever the state was changed on purpose.
+    pub(crate) fn record(&self, state: &U) {
+        self.expected.set((self.hash)(state));
+    }
+
+    // Called before the state is read or changed, reports changes made since the last `record`.
+    pub(crate) fn verify(&self, state: &U, version: u64) {
+        let hash = (self.hash)(state);
+
+        if hash != self.expected.get() {
+            self.expected.set(hash);
+            (self.on_violation)(version);
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Starts checking that the state is only changed through messages, and logs a warning with
+    /// `log::warn!` when it wasn't.
+    ///
+    /// The state is hashed after every dispatch and again whenever it's read or dispatched to, so a
+    /// change made through interior mutability (or anything else that bypasses `msg`) is reported
+    /// the next time the universe is used. This hashes the whole state on every read, so it's meant
+    /// for development builds only.
+    pub fn enable_invariant_check(&self)
+    where
+        U: Hash,
+    {
+        self.enable_invariant_check_with(|version| {
+            log::warn!(
+                "The state was changed outside of `msg` after version {}",
+                version
+            );
+        });
+    }
+
+    /// Starts checking the state like `enable_invariant_check`, but calls `on_violation` with the
+    /// version the state was changed after instead of logging, e.g. to panic.
+    pub fn enable_invariant_check_with(&self, on_violation: impl Fn(u64) + 'static)
+    where
+        U: Hash,
+    {
+        let check = InvariantCheck {
+            hash: Box::new(|state: &U| {
+                let mut hasher = DefaultHasher::new();
+                state.hash(&mut hasher);
+                hasher.finish()
+            }),
+            expected: Cell::new(0),
+            on_violation: Box::new(on_violation),
+        };
+        check.record(&self.inner.universe.borrow());
+
+        *self.inner.invariant_check.borrow_mut() = Some(check);
+    }
+
+    pub(crate) fn verify_invariant(&self, state: &U) {
+        if let Some(check) = self.inner.invariant_check.borrow().as_ref() {
+            check.verify(state, self.inner.version.get());
+        }
+    }
+
+    pub(crate) fn record_invariant(&self, state: &U) {
+        if let Some(check) = self.inner.invariant_check.borrow().as_ref() {
+            check.record(state);
+        }
+    }
+}
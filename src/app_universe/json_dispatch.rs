@@ -0,0 +1,57 @@
+use super::{AppUniverse, AppUniverseCore};
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+/// The error returned when `AppUniverse::dispatch_json` can't turn its input into a message.
+#[derive(Debug)]
+pub enum DispatchJsonError {
+    /// The input isn't valid JSON.
+    Malformed(serde_json::Error),
+    /// The input is valid JSON, but it doesn't describe any message.
+    NoMatchingMessage(serde_json::Error),
+}
+
+impl fmt::Display for DispatchJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DispatchJsonError::Malformed(error) => write!(f, "Malformed JSON: {}", error),
+            DispatchJsonError::NoMatchingMessage(error) => {
+                write!(f, "JSON doesn't match any message: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DispatchJsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DispatchJsonError::Malformed(error) | DispatchJsonError::NoMatchingMessage(error) => {
+                Some(error)
+            }
+        }
+    }
+}
+
+impl From<serde_json::Error> for DispatchJsonError {
+    fn from(error: serde_json::Error) -> Self {
+        match error.classify() {
+            serde_json::error::Category::Data => DispatchJsonError::NoMatchingMessage(error),
+            _ => DispatchJsonError::Malformed(error),
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Deserializes a message from `json`, e.g. `{"Increment": 5}`, and dispatches it with `msg`.
+    ///
+    /// This is meant for debug consoles and devtools. Nothing is dispatched if `json` can't be
+    /// deserialized into a message.
+    pub fn dispatch_json(&self, json: &str) -> Result<(), DispatchJsonError>
+    where
+        U::Message: DeserializeOwned,
+    {
+        let message = serde_json::from_str(json)?;
+        self.msg(message);
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use super::{AppUniverse, AppUniverseCore, UniverseSubscription};
+use serde::Serialize;
+use serde_json::Value;
+
+/// A `PatchOp` is one operation of an [RFC 6902](https://www.rfc-editor.org/rfc/rfc6902) JSON
+/// patch, and serializes to its JSON form, e.g. `{"op":"replace","path":"/counter","value":1}`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    /// Adds `value` at `path`.
+    Add {
+        /// The JSON pointer of the new value.
+        path: String,
+        /// The new value.
+        value: Value,
+    },
+    /// Removes the value at `path`.
+    Remove {
+        /// The JSON pointer of the removed value.
+        path: String,
+    },
+    /// Replaces the value at `path` with `value`.
+    Replace {
+        /// The JSON pointer of the replaced value.
+        path: String,
+        /// The new value.
+        value: Value,
+    },
+}
+
+impl<U: AppUniverseCore + Serialize + 'static> AppUniverse<U> {
+    /// This function takes a subscriber function that receives the JSON patch from the state of
+    /// the previous notification to the current one, e.g. to send only what changed to a server.
+    ///
+    /// The state is serialized to JSON on every notification and compared with the previous
+    /// serialization, so every change pays for a full serialization and a walk over both values.
+    /// For frequently changing states consider throttling, e.g. with a deferred notification
+    /// strategy. The subscriber isn't called when the patch is empty, and notifications whose
+    /// state can't be serialized are logged and skipped.
+    ///
+    /// Arrays are compared index by index, so inserting at the front of an array replaces every
+    /// element after it instead of producing a single `add`.
+    pub fn subscribe_json_patch(
+        &mut self,
+        mut subscriber_fn: Box<dyn FnMut(Vec<PatchOp>)>,
+    ) -> UniverseSubscription<U> {
+        let mut previous = serialize_state(&*self.read());
+
+        self.subscribe(move |universe| {
+            let current = match serialize_state(&*universe.read()) {
+                Some(current) => current,
+                None => return,
+            };
+
+            let mut ops = vec![];
+            if let Some(previous) = &previous {
+                diff(previous, &current, &mut String::new(), &mut ops);
+            } else {
+                ops.push(PatchOp::Replace {
+                    path: String::new(),
+                    value: current.clone(),
+                });
+            }
+            previous = Some(current);
+
+            if !ops.is_empty() {
+                subscriber_fn(ops);
+            }
+        })
+    }
+}
+
+fn serialize_state<U: Serialize>(state: &U) -> Option<Value> {
+    match serde_json::to_value(state) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            log::error!("State could not be serialized for a JSON patch: {}", error);
+            None
+        }
+    }
+}
+
+// Appends the operations that turn `from` into `to` to `ops`, `path` being the pointer to both.
+fn diff(from: &Value, to: &Value, path: &mut String, ops: &mut Vec<PatchOp>) {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            for (key, from_value) in from {
+                let len = push_segment(path, key);
+                match to.get(key) {
+                    Some(to_value) => diff(from_value, to_value, path, ops),
+                    None => ops.push(PatchOp::Remove { path: path.clone() }),
+                }
+                path.truncate(len);
+            }
+
+            for (key, to_value) in to {
+                if !from.contains_key(key) {
+                    let len = push_segment(path, key);
+                    ops.push(PatchOp::Add {
+                        path: path.clone(),
+                        value: to_value.clone(),
+                    });
+                    path.truncate(len);
+                }
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => {
+            for (index, (from_value, to_value)) in from.iter().zip(to).enumerate() {
+                let len = push_segment(path, &index.to_string());
+                diff(from_value, to_value, path, ops);
+                path.truncate(len);
+            }
+
+            // Removing from the back keeps the indices of the remaining removals valid.
+            for index in (to.len()..from.len()).rev() {
+                let len = push_segment(path, &index.to_string());
+                ops.push(PatchOp::Remove { path: path.clone() });
+                path.truncate(len);
+            }
+
+            for (index, to_value) in to.iter().enumerate().skip(from.len()) {
+                let len = push_segment(path, &index.to_string());
+                ops.push(PatchOp::Add {
+                    path: path.clone(),
+                    value: to_value.clone(),
+                });
+                path.truncate(len);
+            }
+        }
+        (from, to) if from != to => ops.push(PatchOp::Replace {
+            path: path.clone(),
+            value: to.clone(),
+        }),
+        _ => {}
+    }
+}
+
+// Appends `segment` to the JSON pointer `path`, escaped as RFC 6901 requires, and returns the
+// length `path` had before.
+fn push_segment(path: &mut String, segment: &str) -> usize {
+    let len = path.len();
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    len
+}
@@ -0,0 +1,67 @@
+use super::{AppUniverse, AppUniverseCore, UniverseError, UniverseSubscription};
+use std::{borrow::Borrow, cell::Ref, rc::Rc};
+
+/// A `MappedUniverse` is a read-only handle to a part of the state of another universe, created
+/// with `AppUniverse::map`. It's meant to be handed to components that only care about that part.
+///
+/// Unlike a `DerivedUniverse` it doesn't hold a copy: `read` borrows straight from the source
+/// universe. It can't be dispatched to. Cloning it is cheap.
+pub struct MappedUniverse<U: AppUniverseCore, T: ?Sized> {
+    universe: AppUniverse<U>,
+    map: Rc<dyn Fn(&U) -> &T>,
+}
+
+impl<U: AppUniverseCore + 'static, T: ?Sized + 'static> MappedUniverse<U, T> {
+    /// Acquire read access to the mapped part of the state.
+    pub fn read(&self) -> Ref<'_, T> {
+        Ref::map(self.universe.read(), |state| (self.map)(state))
+    }
+
+    /// This function takes a `callback` that runs with the mapped part of the state anytime it
+    /// changes. Changes to the rest of the state don't run it.
+    ///
+    /// The subscription belongs to the source universe, and is removed with `unsubscribe`.
+    ///
+    /// The part is compared with the one the callback last ran with, so a copy of it is kept.
+    pub fn subscribe(&self, mut callback: impl FnMut(&T) + 'static) -> UniverseSubscription<U>
+    where
+        T: PartialEq + ToOwned,
+        T::Owned: 'static,
+    {
+        let map = self.map.clone();
+
+        self.universe.clone().subscribe_selector(
+            move |state| map(state).to_owned(),
+            |last: &T::Owned, value: &T::Owned| last.borrow() == value.borrow(),
+            move |value| callback(value.borrow()),
+        )
+    }
+
+    /// This function removes a subscription made with `subscribe`.
+    pub fn unsubscribe(&self, subscription: UniverseSubscription<U>) -> Result<(), UniverseError> {
+        self.universe.clone().unsubscribe(subscription)
+    }
+}
+
+impl<U: AppUniverseCore, T: ?Sized> Clone for MappedUniverse<U, T> {
+    fn clone(&self) -> Self {
+        MappedUniverse {
+            universe: self.universe.clone(),
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Returns a read-only `MappedUniverse` for the part of the state that `map` points to, e.g.
+    /// `universe.map(|state| &state.settings)`.
+    pub fn map<T: ?Sized + 'static>(
+        &self,
+        map: impl Fn(&U) -> &T + 'static,
+    ) -> MappedUniverse<U, T> {
+        MappedUniverse {
+            universe: self.clone(),
+            map: Rc::new(map),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use super::AppUniverseCore;
+use std::{cell::RefCell, rc::Rc};
+
+// Selects the inputs from the state and returns the recomputed value if they changed.
+type Recompute<U, T> = Box<dyn FnMut(&U) -> Option<T>>;
+
+struct MemoInner<U, T> {
+    recompute: Recompute<U, T>,
+    value: Option<Rc<T>>,
+}
+
+/// A `Memo` caches a value derived from the state, and only recomputes it when the inputs it was
+/// derived from change, like a `reselect` selector. It's meant for expensive derived data, e.g. a
+/// filtered and sorted list.
+///
+/// It's pull-based: nothing is computed until `get` is called. Cloning it is cheap, and all clones
+/// share the same cache, so a clone can be moved into every subscriber that needs the value.
+pub struct Memo<U, T> {
+    inner: Rc<RefCell<MemoInner<U, T>>>,
+}
+
+impl<U: AppUniverseCore + 'static, T: 'static> Memo<U, T> {
+    /// Creates a memo that selects its inputs from the state with `select` and derives its value
+    /// from them with `compute`.
+    ///
+    /// `select` runs on every `get` and should be cheap, e.g. clone a few fields. `compute` only
+    /// runs when the inputs differ from the previous ones.
+    pub fn new<I: PartialEq + 'static>(
+        select: impl Fn(&U) -> I + 'static,
+        compute: impl Fn(&I) -> T + 'static,
+    ) -> Self {
+        let mut inputs: Option<I> = None;
+
+        Memo {
+            inner: Rc::new(RefCell::new(MemoInner {
+                recompute: Box::new(move |state| {
+                    let selected = select(state);
+                    if inputs.as_ref() == Some(&selected) {
+                        return None;
+                    }
+
+                    let value = compute(&selected);
+                    inputs = Some(selected);
+                    Some(value)
+                }),
+                value: None,
+            })),
+        }
+    }
+
+    /// Returns the value for `state`, recomputing it first if the inputs changed since the last
+    /// call, e.g. `memo.get(&universe.read())`.
+    pub fn get(&self, state: &U) -> Rc<T> {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(value) = (inner.recompute)(state) {
+            inner.value = Some(Rc::new(value));
+        }
+
+        inner.value.clone().expect("computed on the first call")
+    }
+}
+
+impl<U, T> Clone for Memo<U, T> {
+    fn clone(&self) -> Self {
+        Memo {
+            inner: self.inner.clone(),
+        }
+    }
+}
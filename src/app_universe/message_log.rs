@@ -0,0 +1,193 @@
+use super::{AppUniverse, AppUniverseCore, UniverseError};
+
+// The messages applied while recording, each with the version it produced.
+pub(crate) struct MessageLog<U: AppUniverseCore> {
+    clone: fn(&U::Message) -> U::Message,
+    entries: Vec<(u64, U::Message)>,
+    base: Option<ReplayBase<U>>,
+}
+
+// The state recording started from, which the recorded messages can be replayed onto.
+struct ReplayBase<U> {
+    state: U,
+    clone: fn(&U) -> U,
+}
+
+impl<U: AppUniverseCore> MessageLog<U> {
+    pub(crate) fn record(&mut self, version: u64, message: &U::Message) {
+        self.entries.push((version, (self.clone)(message)));
+    }
+
+    // Forgets the messages that produced versions after `version`, e.g. after a rollback.
+    pub(crate) fn truncate_after(&mut self, version: u64) {
+        self.entries.retain(|(produced, _)| *produced <= version);
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U>
+where
+    U::Message: Clone,
+{
+    /// Starts recording every applied message together with the version it produced, so devtools
+    /// can fetch them with `messages_since`. Calling this again clears the recorded messages.
+    ///
+    /// The log keeps a clone of every message until `stop_recording_messages` is called, so it
+    /// grows for as long as recording is on. Messages produced from the state, like the ones of
+    /// `dispatch_fold`, and changes made with `with_mut`, `replace_state`, `reset_with` and
+    /// `restore_snapshot` aren't recorded. The messages of a rolled back `transaction` are removed
+    /// from the log again.
+    pub fn record_messages(&self) {
+        *self.inner.message_log.borrow_mut() = Some(MessageLog {
+            clone: U::Message::clone,
+            entries: vec![],
+            base: None,
+        });
+    }
+
+    /// Returns clones of the recorded messages that were applied after `version`, in order.
+    ///
+    /// Passing the last version a devtools frontend has seen returns only what's new, so the whole
+    /// log never has to be sent again.
+    pub fn messages_since(&self, version: u64) -> Vec<U::Message> {
+        match self.inner.message_log.borrow().as_ref() {
+            Some(log) => log
+                .entries
+                .iter()
+                .filter(|(produced, _)| *produced > version)
+                .map(|(_, message)| message.clone())
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+impl<U: AppUniverseCore + Clone + 'static> AppUniverse<U>
+where
+    U::Message: Clone,
+{
+    /// Starts recording messages like `record_messages`, and keeps a clone of the current state so
+    /// `recover_by_replay` can rebuild the state from it.
+    pub fn enable_replay_recovery(&self) {
+        let state = self.inner.universe.borrow().clone();
+
+        *self.inner.message_log.borrow_mut() = Some(MessageLog {
+            clone: U::Message::clone,
+            entries: vec![],
+            base: Some(ReplayBase {
+                state,
+                clone: U::clone,
+            }),
+        });
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Rebuilds the state by replaying the recorded messages onto the state kept by
+    /// `enable_replay_recovery`, and notifies subscribers.
+    ///
+    /// It's meant to be called after a panic in a reducer or subscriber was caught, when the state
+    /// may have been left half-updated. The dispatch that panicked never reached its version, so
+    /// its messages are discarded and the rebuilt state matches the current version. Changes that
+    /// aren't recorded as messages, made with `dispatch_fold`, `with_mut`, `replace_state`,
+    /// `reset_with` or `restore_snapshot`, are lost. Rolled back transactions are skipped, since
+    /// their messages were removed from the log.
+    ///
+    /// Returns an error if `enable_replay_recovery` wasn't called or recording was stopped.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called while a dispatch is being applied, e.g. from a subscriber.
+    pub fn recover_by_replay(&self) -> Result<(), UniverseError> {
+        assert!(
+            !self.inner.dispatch_queue.is_draining(),
+            "recover_by_replay can't be called while a dispatch is being applied"
+        );
+
+        let state = {
+            let mut message_log = self.inner.message_log.borrow_mut();
+            let log = match message_log.as_mut() {
+                Some(log) => log,
+                None => return Err(UniverseError::ReplayRecoveryDisabled),
+            };
+            let base = match &log.base {
+                Some(base) => base,
+                None => return Err(UniverseError::ReplayRecoveryDisabled),
+            };
+
+            let mut state = (base.clone)(&base.state);
+            log.truncate_after(self.inner.version.get());
+
+            for (_, message) in &log.entries {
+                state.msg((log.clone)(message));
+            }
+
+            state
+        };
+
+        *self.inner.universe.borrow_mut() = state;
+
+        #[cfg(feature = "debug-invariant")]
+        self.record_invariant(&self.inner.universe.borrow());
+
+        if self.state_changed() {
+            self.schedule_notification();
+        }
+
+        Ok(())
+    }
+
+    /// Stops recording messages and frees the recorded ones.
+    pub fn stop_recording_messages(&self) {
+        self.inner.message_log.borrow_mut().take();
+    }
+
+    pub(crate) fn record_message(&self, version: u64, message: &U::Message) {
+        if let Some(log) = self.inner.message_log.borrow_mut().as_mut() {
+            log.record(version, message);
+        }
+    }
+}
+
+#[cfg(feature = "test-utils")]
+impl<U: AppUniverseCore + Clone + PartialEq + std::fmt::Debug + 'static> AppUniverse<U>
+where
+    U::Message: Clone,
+{
+    /// This function is used in tests to check that replaying the recorded messages is
+    /// deterministic, e.g. that no reducer reads a clock or a random number generator.
+    ///
+    /// The messages recorded since `enable_replay_recovery` are replayed twice onto the state it
+    /// kept, comparing both states after every message.
+    ///
+    /// # Panics
+    ///
+    /// Panics with both states and the message they diverged at if the replays differ, or if
+    /// `enable_replay_recovery` wasn't called.
+    pub fn assert_replay_deterministic(&self) {
+        let message_log = self.inner.message_log.borrow();
+        let (log, base) = match message_log.as_ref() {
+            Some(log) => match &log.base {
+                Some(base) => (log, base),
+                None => panic!("Replay recovery isn't enabled"),
+            },
+            None => panic!("Replay recovery isn't enabled"),
+        };
+
+        let mut first = (base.clone)(&base.state);
+        let mut second = (base.clone)(&base.state);
+
+        for (index, (version, message)) in log.entries.iter().enumerate() {
+            first.msg((log.clone)(message));
+            second.msg((log.clone)(message));
+
+            assert!(
+                first == second,
+                "Replay is not deterministic, it diverged at recorded message {} (version {}):\n  first replay: {:?}\n second replay: {:?}",
+                index,
+                version,
+                first,
+                second,
+            );
+        }
+    }
+}
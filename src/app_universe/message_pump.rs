@@ -0,0 +1,42 @@
+use super::{AppUniverse, AppUniverseCore};
+use futures::{Stream, StreamExt};
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Dispatches every message produced by `stream` with `msg` as it arrives, until the stream
+    /// ends or the universe is closed.
+    ///
+    /// This is the input-side counterpart to subscribing, meant for message sources such as
+    /// websocket frames or keyboard events. If the universe is closed while the stream is pending,
+    /// pumping stops when the next message arrives and that message is dropped.
+    ///
+    /// ```rust
+    /// # use app_universe::{AppUniverse, AppUniverseCore};
+    /// # struct Counter { value: u32 }
+    /// # impl AppUniverseCore for Counter {
+    /// #     type Message = u32;
+    /// #     fn msg(&mut self, amount: u32) { self.value += amount; }
+    /// # }
+    /// use futures::channel::mpsc;
+    ///
+    /// let universe = AppUniverse::new(Counter { value: 0 });
+    /// let (sender, receiver) = mpsc::unbounded();
+    ///
+    /// // Anything holding the sender can now feed the universe.
+    /// sender.unbounded_send(1).unwrap();
+    /// sender.unbounded_send(2).unwrap();
+    /// drop(sender);
+    ///
+    /// futures::executor::block_on(universe.pump(receiver));
+    /// assert_eq!(universe.read().value, 3);
+    /// ```
+    pub async fn pump(&self, stream: impl Stream<Item = U::Message>) {
+        let mut stream = Box::pin(stream);
+
+        while !self.is_closed() {
+            match stream.next().await {
+                Some(message) if !self.is_closed() => self.msg(message),
+                _ => break,
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use super::{AppUniverse, AppUniverseCore};
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+/// A `Clock` tells the time for `subscriber_metrics`.
+///
+/// Only differences between two readings are used, so it may count from any starting point.
+pub trait Clock {
+    /// Returns the time elapsed since the starting point of the clock.
+    fn now(&self) -> Duration;
+}
+
+/// A `ManualClock` only moves when it's told to, e.g. to make timings deterministic in tests.
+///
+/// Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock(Rc<Cell<Duration>>);
+
+impl ManualClock {
+    /// Creates a clock that stands at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.0.set(self.0.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.0.get()
+    }
+}
+
+// The clock every universe starts with. `Instant` isn't available on wasm32, so there's none there.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn default_clock() -> Option<Rc<dyn Clock>> {
+    struct InstantClock(std::time::Instant);
+
+    impl Clock for InstantClock {
+        fn now(&self) -> Duration {
+            self.0.elapsed()
+        }
+    }
+
+    Some(Rc::new(InstantClock(std::time::Instant::now())))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn default_clock() -> Option<Rc<dyn Clock>> {
+    None
+}
+
+// How often a subscriber ran and for how long in total.
+#[derive(Default)]
+pub(crate) struct SubscriberTiming {
+    invocations: Cell<u64>,
+    total: Cell<Duration>,
+}
+
+impl SubscriberTiming {
+    pub(crate) fn record(&self, duration: Duration) {
+        self.invocations.set(self.invocations.get() + 1);
+        self.total.set(self.total.get() + duration);
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Sets the clock used to time subscribers.
+    ///
+    /// It defaults to one based on `std::time::Instant`, except on wasm32 where there is no default
+    /// and subscribers aren't timed until a clock is set.
+    pub fn set_clock(&self, clock: impl Clock + 'static) {
+        *self.inner.clock.borrow_mut() = Some(Rc::new(clock));
+    }
+
+    /// Returns how often every registered subscriber ran and how long it took in total, in the
+    /// order they are notified.
+    ///
+    /// Subscribers are identified by their label, or by `#` and their id when they have none.
+    /// This is only available with the `metrics` feature, other builds don't time subscribers.
+    pub fn subscriber_metrics(&self) -> Vec<(String, u64, Duration)> {
+        self.inner
+            .subscriptions
+            .borrow()
+            .iter()
+            .map(|subscription| {
+                let subscription = subscription.borrow();
+                let label = match &subscription.label {
+                    Some(label) => label.clone(),
+                    None => format!("#{}", subscription.id.0),
+                };
+
+                (
+                    label,
+                    subscription.timing.invocations.get(),
+                    subscription.timing.total.get(),
+                )
+            })
+            .collect()
+    }
+
+    pub(crate) fn now(&self) -> Option<Duration> {
+        self.inner.clock.borrow().as_ref().map(|clock| clock.now())
+    }
+}
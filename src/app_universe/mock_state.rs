@@ -0,0 +1,51 @@
+use super::{AppUniverse, AppUniverseCore};
+
+// Puts the original state back and thaws the universe when it's dropped, including while
+// unwinding from a panic.
+struct RestoreGuard<'a, U: AppUniverseCore + 'static> {
+    universe: &'a AppUniverse<U>,
+    original: Option<U>,
+    was_frozen: bool,
+}
+
+impl<U: AppUniverseCore + 'static> Drop for RestoreGuard<'_, U> {
+    fn drop(&mut self) {
+        self.universe.inner.frozen.set(self.was_frozen);
+
+        if let Some(original) = self.original.take() {
+            let mut universe = self.universe.inner.universe.borrow_mut();
+            *universe = original;
+
+            #[cfg(feature = "debug-invariant")]
+            self.universe.record_invariant(&universe);
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function is used in tests to check how subscribers react to `state` without crafting
+    /// the messages that lead to it.
+    ///
+    /// The state is replaced by `state` and subscribers are notified, then `f` runs to make
+    /// assertions, and finally the original state is put back without notifying anyone. The
+    /// original state is restored even if a subscriber or `f` panics, so a failing assertion
+    /// doesn't leak the mocked state into the rest of the test. The version isn't changed.
+    ///
+    /// Anything dispatched to the mocked state would be lost with it, so the universe is frozen
+    /// while the mock is active: dispatching from a subscriber or from `f` panics in debug builds,
+    /// like dispatching to a frozen universe does.
+    pub fn with_mocked_state(&self, state: U, f: impl FnOnce(&Self)) {
+        let original = std::mem::replace(&mut *self.inner.universe.borrow_mut(), state);
+        let _guard = RestoreGuard {
+            universe: self,
+            original: Some(original),
+            was_frozen: self.inner.frozen.replace(true),
+        };
+
+        #[cfg(feature = "debug-invariant")]
+        self.record_invariant(&self.inner.universe.borrow());
+
+        self.notify_subscribers();
+        f(self);
+    }
+}
@@ -1,17 +1,241 @@
 #![deny(missing_docs)]
 
+mod adapt;
+#[cfg(feature = "async")]
+mod async_dispatch;
+#[cfg(feature = "async")]
+mod async_status;
+mod batch;
+mod builder;
+#[cfg(feature = "test-utils")]
+mod capture;
+mod change_cursor;
+#[cfg(feature = "async")]
+mod change_waiter;
+mod coalescing;
+mod countable;
+mod debounce;
+mod derived;
+#[cfg(any(feature = "test-utils", feature = "devtools"))]
+mod devtools;
+mod dispatch;
+mod dispatcher;
+mod error;
+mod freeze;
+#[cfg(feature = "gtk")]
+mod glib_bridge;
+#[cfg(feature = "serde")]
+mod hydration;
+#[cfg(feature = "iced")]
+mod iced_bridge;
+#[cfg(feature = "indexeddb-storage")]
+mod indexeddb_storage;
+#[cfg(feature = "debug-invariant")]
+mod invariant_check;
+#[cfg(feature = "serde")]
+mod json_dispatch;
+#[cfg(feature = "serde")]
+mod json_patch;
+mod mapped;
+mod memo;
+mod message_log;
+#[cfg(feature = "futures")]
+mod message_pump;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "test-utils")]
+mod mock_state;
+mod notification;
+#[cfg(feature = "test-utils")]
+mod notification_log;
+mod observation;
+mod observer;
+mod once;
+#[cfg(all(feature = "serde", feature = "async"))]
+mod persistence;
+mod previous_state;
+mod pure;
+mod read_cow;
+mod reader;
+mod replace_state;
+mod scheduled;
+mod selector;
+#[cfg(feature = "futures-signals")]
+mod signals;
+mod snapshot;
+mod subscriber_ctx;
+mod subscriber_errors;
+mod subscription_group;
+mod subscription_guard;
+mod subscription_meta;
+#[cfg(feature = "sycamore")]
+mod sycamore_bridge;
+#[cfg(feature = "tauri")]
+mod tauri_bridge;
+mod threaded;
+mod transaction;
+mod universe_dispatcher;
+mod update_guard;
+mod versioned_ref;
+mod weak_universe;
+#[cfg(feature = "worker-bridge")]
+mod worker_bridge;
+
+pub use adapt::{adapt, Adapted};
+#[cfg(feature = "async")]
+pub use async_dispatch::{LocalBoxFuture, Spawner};
+#[cfg(feature = "async")]
+pub use async_status::AsyncStatus;
+pub use batch::BatchInfo;
+pub use builder::AppUniverseBuilder;
+pub use change_cursor::ChangeCursor;
+pub use coalescing::Scheduler;
+pub use countable::Countable;
+pub use debounce::{DebounceHandle, Debouncer};
+pub use derived::DerivedUniverse;
+#[cfg(any(feature = "test-utils", feature = "devtools"))]
+pub use devtools::SubscriptionInfo;
+pub use dispatch::{DispatchId, DispatchPriority};
+pub use dispatcher::Dispatcher;
+pub use error::UniverseError;
+#[cfg(feature = "gtk")]
+pub use glib_bridge::{GlibUniverseBridge, UniverseSender};
+#[cfg(feature = "serde")]
+pub use hydration::HydrateError;
+#[cfg(feature = "iced")]
+pub use iced_bridge::{forward_to_universe, UniverseSubscriptionRecipe};
+#[cfg(feature = "indexeddb-storage")]
+pub use indexeddb_storage::IndexedDbStorage;
+#[cfg(feature = "serde")]
+pub use json_dispatch::DispatchJsonError;
+#[cfg(feature = "serde")]
+pub use json_patch::PatchOp;
+pub use mapped::MappedUniverse;
+pub use memo::Memo;
+#[cfg(feature = "metrics")]
+pub use metrics::{Clock, ManualClock};
+pub use notification::{MessagePriority, NotificationStrategy};
+#[cfg(feature = "test-utils")]
+pub use notification_log::{NotificationLog, SubscriberInvocation};
+pub use observation::Observation;
+pub use observer::ObserverCore;
+#[cfg(all(feature = "serde", feature = "async"))]
+pub use persistence::{AsyncStorage, StorageError, StorageFuture};
+pub use pure::PureUniverseCore;
+pub use read_cow::ReadCow;
+pub use reader::UniverseReader;
+pub use scheduled::{ImmediateScheduler, TickScheduler};
+pub use selector::{approx_eq, approx_eq_within, ApproxEq, DEFAULT_EPSILON};
+pub use snapshot::Snapshot;
+pub use subscriber_ctx::SubscriberCtx;
+pub use subscription_group::SubscriptionGroup;
+pub use subscription_guard::SubscriptionGuard;
+pub use subscription_meta::SubscriptionMeta;
+#[cfg(feature = "tauri")]
+pub use tauri_bridge::{TauriUniversePlugin, UNIVERSE_CHANGED_EVENT};
+pub use threaded::{AppUniverseThreaded, ThreadedSubscription};
+#[cfg(feature = "async")]
+pub use threaded::{SendBoxFuture, SendSpawner};
+pub use universe_dispatcher::UniverseDispatcher;
+pub use update_guard::UpdateGuard;
+pub use versioned_ref::VersionedRef;
+pub use weak_universe::WeakAppUniverse;
+#[cfg(feature = "worker-bridge")]
+pub use worker_bridge::{
+    WorkerClientSubscription, WorkerPort, WorkerUniverseClient, WorkerUniverseHost,
+};
+
 use std::{
-    cell::{Ref, RefCell},
+    cell::{Cell, Ref, RefCell},
     rc::Rc,
 };
 
+#[cfg(feature = "async")]
+use std::future::Future;
+
+type SubscriberFn<U> = Box<dyn FnMut(AppUniverse<U>)>;
+type CtxSubscriberFn<U> = Box<dyn FnMut(&SubscriberCtx<'_, U>)>;
+
+/// A `SubscriptionId` uniquely identifies a subscription within a universe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SubscriptionId(u64);
+
+/// The `SubscriberPriority` decides the order in which subscribers are notified.
+///
+/// Subscribers run from the lowest priority to the highest, so `First` runs before any `Value` and
+/// `Last` runs after them. Subscribers with equal priorities run in the order they subscribed.
+/// Subscriptions have `SubscriberPriority::default()` (`Value(0)`) unless specified otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SubscriberPriority {
+    /// Runs before every other priority.
+    First,
+    /// Runs in ascending order of the value.
+    Value(i32),
+    /// Runs after every other priority.
+    Last,
+}
+
+impl Default for SubscriberPriority {
+    fn default() -> Self {
+        SubscriberPriority::Value(0)
+    }
+}
+
 #[cfg(not(feature = "test-utils"))]
 // This is the internal subscription used to hold the subscriber function.
-struct Subscription<U: AppUniverseCore>(Box<dyn FnMut(AppUniverse<U>)>);
+struct Subscription<U: AppUniverseCore> {
+    id: SubscriptionId,
+    callback: RefCell<CtxSubscriberFn<U>>,
+    unsubscribed: Cell<bool>,
+    key: Option<String>,
+    label: Option<String>,
+    priority: Cell<SubscriberPriority>,
+    #[cfg(any(feature = "test-utils", feature = "devtools"))]
+    created_version: u64,
+    #[cfg(feature = "metrics")]
+    timing: metrics::SubscriberTiming,
+}
 
 #[cfg(feature = "test-utils")]
 /// This is a version of the subscription above that is public for testing purposes
-pub struct Subscription<U: AppUniverseCore>(Box<dyn FnMut(AppUniverse<U>)>);
+pub struct Subscription<U: AppUniverseCore> {
+    id: SubscriptionId,
+    callback: RefCell<CtxSubscriberFn<U>>,
+    unsubscribed: Cell<bool>,
+    key: Option<String>,
+    label: Option<String>,
+    priority: Cell<SubscriberPriority>,
+    #[cfg(any(feature = "test-utils", feature = "devtools"))]
+    created_version: u64,
+    #[cfg(feature = "metrics")]
+    timing: metrics::SubscriberTiming,
+}
+
+impl<U: AppUniverseCore + 'static> Subscription<U> {
+    fn new(id: SubscriptionId, mut callback: SubscriberFn<U>) -> Self {
+        Self::with_ctx(
+            id,
+            Box::new(move |ctx: &SubscriberCtx<'_, U>| callback(ctx.universe.clone())),
+        )
+    }
+
+    fn with_ctx(id: SubscriptionId, callback: CtxSubscriberFn<U>) -> Self {
+        Subscription {
+            id,
+            callback: RefCell::new(callback),
+            unsubscribed: Cell::new(false),
+            key: None,
+            label: None,
+            priority: Cell::new(SubscriberPriority::default()),
+            #[cfg(any(feature = "test-utils", feature = "devtools"))]
+            created_version: 0,
+            #[cfg(feature = "metrics")]
+            timing: Default::default(),
+        }
+    }
+}
 
 type UniverseSubscriptionParameter<U> = Rc<RefCell<Subscription<U>>>;
 
@@ -19,16 +243,72 @@ type UniverseSubscriptionParameter<U> = Rc<RefCell<Subscription<U>>>;
 /// whenever the `subscribe` method on `AppUniverse` is called.
 /// Its only purpose is to be passed into the `unsubscribe` method on
 /// `AppUniverse` whenever it's called.
+#[must_use = "ignoring the subscription handle means you can never unsubscribe"]
 pub struct UniverseSubscription<U: AppUniverseCore>(UniverseSubscriptionParameter<U>);
 
+impl<U: AppUniverseCore> UniverseSubscription<U> {
+    /// Returns the id of this subscription.
+    pub fn id(&self) -> SubscriptionId {
+        self.0.borrow().id
+    }
+
+    /// Returns the label of this subscription, if it was given one.
+    pub fn label(&self) -> Option<String> {
+        self.0.borrow().label.clone()
+    }
+}
+
+/// Cloning a `UniverseSubscription` is cheap, and all clones refer to the same subscription.
+impl<U: AppUniverseCore> Clone for UniverseSubscription<U> {
+    fn clone(&self) -> Self {
+        UniverseSubscription(self.0.clone())
+    }
+}
+
 /// This is the holds the application state (universe) and the subscriptions to
 /// that state. The only way to read information about the state publicly is by calling
 /// the `read` method on `AppUniverse`. There is no way to publicly access the subscriptions.
 ///
 /// Cloning the AppUniverse is really cheap and all clones hold pointers to the same inner state.
 pub struct AppUniverse<U: AppUniverseCore> {
+    inner: Rc<UniverseInner<U>>,
+}
+
+// The state shared by every handle to a universe. `WeakAppUniverse` points at the same
+// allocation, so a single `Rc` decides whether the universe is still alive.
+pub(crate) struct UniverseInner<U: AppUniverseCore> {
     universe: Rc<RefCell<U>>,
-    subscriptions: Rc<RefCell<Vec<UniverseSubscriptionParameter<U>>>>,
+    subscriptions: RefCell<Vec<UniverseSubscriptionParameter<U>>>,
+    version: Cell<u64>,
+    next_subscription_id: Cell<u64>,
+    subscriber_error_handler: RefCell<subscriber_errors::ErrorHandler>,
+    dispatch_queue: dispatch::DispatchQueue<U>,
+    notifier: notification::Notifier,
+    closed: Cell<bool>,
+    frozen: Cell<bool>,
+    observers: RefCell<Option<observer::Observers<U>>>,
+    message_log: RefCell<Option<message_log::MessageLog<U>>>,
+    notified_version: Cell<u64>,
+    pending_subscriptions: RefCell<Vec<UniverseSubscriptionParameter<U>>>,
+    notifying: Cell<bool>,
+    change_detector: RefCell<Option<builder::ChangeDetector<U>>>,
+    coalescer: RefCell<coalescing::Coalescer<U>>,
+    #[cfg(feature = "async")]
+    change_waiters: change_waiter::ChangeWaiters,
+    #[cfg(feature = "async")]
+    spawner: RefCell<Option<Rc<dyn Spawner>>>,
+    #[cfg(feature = "async")]
+    async_statuses: RefCell<async_status::AsyncStatusTable>,
+    #[cfg(all(feature = "serde", feature = "async"))]
+    storage_error_handler: RefCell<persistence::StorageErrorHandler>,
+    #[cfg(feature = "test-utils")]
+    notification_log: RefCell<Option<NotificationLog>>,
+    #[cfg(feature = "test-utils")]
+    captured: RefCell<Option<Vec<U::Message>>>,
+    #[cfg(feature = "debug-invariant")]
+    invariant_check: RefCell<Option<invariant_check::InvariantCheck<U>>>,
+    #[cfg(feature = "metrics")]
+    clock: RefCell<Option<Rc<dyn Clock>>>,
 }
 
 /// This trait defines the blueprint for the "core" of a universe.
@@ -42,77 +322,1085 @@ pub trait AppUniverseCore: Sized {
     /// The `msg` method should typically mutate state in some way. It should
     /// react to the variant of `Message` sent in as mutate the state.
     fn msg(&mut self, message: Self::Message);
+
+    /// The `msg_with` method handles a message like `msg`, and can dispatch follow-up messages
+    /// through `dispatcher`. The universe always calls this method, which calls `msg` unless it's
+    /// overridden.
+    ///
+    /// Cores that override it still implement `msg`, e.g. by handling the message without any
+    /// follow-ups.
+    fn msg_with(&mut self, message: Self::Message, dispatcher: &Dispatcher<Self>) {
+        let _ = dispatcher;
+        self.msg(message);
+    }
 }
 
 /// This wrapper defines the type of a universe
 impl<U: AppUniverseCore + 'static> AppUniverse<U> {
     /// This creates a new app_universe
     pub fn new(universe_core: U) -> Self {
+        Self::with_subscription_capacity(universe_core, 0)
+    }
+
+    /// This creates a new app_universe with room for `capacity` subscriptions.
+    ///
+    /// This is only an optimization hint for apps that know they will register many subscribers,
+    /// the universe still grows past `capacity` when more subscribers are added.
+    pub fn with_subscription_capacity(universe_core: U, capacity: usize) -> Self {
         let universe = Rc::new(RefCell::new(universe_core));
         Self {
-            universe,
-            subscriptions: Rc::new(RefCell::new(vec![])),
+            inner: Rc::new(UniverseInner {
+                universe,
+                subscriptions: RefCell::new(Vec::with_capacity(capacity)),
+                version: Cell::new(0),
+                next_subscription_id: Cell::new(0),
+                subscriber_error_handler: RefCell::new(Box::new(
+                    subscriber_errors::log_subscriber_error,
+                )),
+                dispatch_queue: dispatch::DispatchQueue::new(),
+                notifier: Default::default(),
+                closed: Cell::new(false),
+                frozen: Cell::new(false),
+                observers: RefCell::new(None),
+                message_log: RefCell::new(None),
+                notified_version: Cell::new(0),
+                pending_subscriptions: RefCell::new(vec![]),
+                notifying: Cell::new(false),
+                change_detector: RefCell::new(None),
+                coalescer: RefCell::new(Default::default()),
+                #[cfg(feature = "async")]
+                change_waiters: Rc::new(RefCell::new(vec![])),
+                #[cfg(feature = "async")]
+                spawner: RefCell::new(None),
+                #[cfg(feature = "async")]
+                async_statuses: RefCell::new(Default::default()),
+                #[cfg(all(feature = "serde", feature = "async"))]
+                storage_error_handler: RefCell::new(Box::new(persistence::log_storage_error)),
+                #[cfg(feature = "test-utils")]
+                notification_log: RefCell::new(None),
+                #[cfg(feature = "test-utils")]
+                captured: RefCell::new(None),
+                #[cfg(feature = "debug-invariant")]
+                invariant_check: RefCell::new(None),
+                #[cfg(feature = "metrics")]
+                clock: RefCell::new(metrics::default_clock()),
+            }),
         }
     }
 
     /// This method allows for mutation of state by sending a message
+    ///
+    /// Messages sent from inside a subscriber are queued and applied once the current
+    /// notification pass is over.
     pub fn msg(&self, msg: U::Message) {
-        self.universe.borrow_mut().msg(msg);
-        for subscriber in self.subscriptions.borrow_mut().iter() {
-            (subscriber.borrow_mut().0)(self.clone());
+        self.dispatch(vec![msg], self.inner.dispatch_queue.current_id());
+    }
+
+    /// This method sends a message like `msg`, but returns an error instead of dispatching when
+    /// the message can't be applied: `UniverseError::Frozen` while the universe is frozen and
+    /// `UniverseError::AlreadyBorrowed` while the state is borrowed outside of a dispatch, which
+    /// would make `msg` panic.
+    ///
+    /// Messages sent from inside a subscriber are queued like with `msg`, so they only fail with
+    /// `UniverseError::DepthLimitExceeded` when they would be nested deeper than the dispatch depth
+    /// limit, where `msg` panics in debug builds and drops the message in release builds.
+    pub fn try_msg(&self, msg: U::Message) -> Result<(), UniverseError> {
+        if self.is_frozen() {
+            return Err(UniverseError::Frozen);
+        }
+        if self.inner.dispatch_queue.exceeds_depth_limit() {
+            return Err(UniverseError::DepthLimitExceeded);
         }
+        if !self.inner.dispatch_queue.is_draining() && self.inner.universe.try_borrow_mut().is_err()
+        {
+            return Err(UniverseError::AlreadyBorrowed);
+        }
+
+        self.msg(msg);
+        Ok(())
+    }
+
+    /// This method sends a message like `msg` and starts a new traced dispatch chain.
+    ///
+    /// The returned `DispatchId` is visible through `current_dispatch_id` while the message is
+    /// applied and its subscribers run. Follow-up messages dispatched during that time, including
+    /// the ones from `dispatch_async`, inherit the same id.
+    pub fn msg_traced(&self, msg: U::Message) -> DispatchId {
+        let id = self.inner.dispatch_queue.next_id();
+        self.dispatch(vec![msg], Some(id));
+        id
+    }
+
+    /// This method sends a message like `msg`, which waits in the `priority` lane of the dispatch
+    /// queue if it can't be applied straight away.
+    ///
+    /// Priorities apply to whole dispatches, so a `msg_batch` is always applied in one go with
+    /// `Normal` priority. Notifications aren't affected, they still follow the notification
+    /// strategy no matter which lane the applied dispatch came from.
+    pub fn msg_with_priority(&self, msg: U::Message, priority: DispatchPriority) {
+        self.enqueue(
+            dispatch::QueuedDispatch::new(vec![msg], self.inner.dispatch_queue.current_id())
+                .with_priority(priority),
+        );
+    }
+
+    /// This method applies `msg` like `msg`, and lets `MessagePriority::Background` messages hold
+    /// back their notification until the next `Interactive` message or `flush`, so background
+    /// updates are batched with the next user-visible one.
+    ///
+    /// This is unrelated to `msg_with_priority`, which decides the order in which queued
+    /// dispatches are applied.
+    pub fn msg_with_notification_priority(&self, msg: U::Message, priority: MessagePriority) {
+        let dispatch =
+            dispatch::QueuedDispatch::new(vec![msg], self.inner.dispatch_queue.current_id());
+
+        self.enqueue(match priority {
+            MessagePriority::Interactive => dispatch,
+            MessagePriority::Background => dispatch.with_notify(notification::Notify::Pending),
+        });
+    }
+
+    /// Sets how many higher-priority dispatches may be applied while a `Low` dispatch is waiting
+    /// before that dispatch is applied anyway. The default is `8`.
+    pub fn set_starvation_limit(&self, limit: u32) {
+        self.inner.dispatch_queue.set_starvation_limit(limit);
+    }
+
+    /// Sets how deeply dispatches may be nested before one is rejected. The default is `100`.
+    ///
+    /// A message sent from a subscriber is nested one level deeper than the dispatch that notified
+    /// the subscriber, so subscribers that keep dispatching in response to each other would loop
+    /// forever. The dispatch past the limit panics in debug builds and is dropped in release builds,
+    /// except with `try_msg`, which returns `UniverseError::DepthLimitExceeded` instead.
+    pub fn set_dispatch_depth_limit(&self, limit: u32) {
+        self.inner.dispatch_queue.set_depth_limit(limit);
+    }
+
+    /// Returns the id of the traced dispatch chain that is currently being processed, if any.
+    pub fn current_dispatch_id(&self) -> Option<DispatchId> {
+        self.inner.dispatch_queue.current_id()
+    }
+
+    /// This method applies several messages in order and then notifies subscribers once.
+    ///
+    /// The version goes up by one for every applied message. If the iterator is empty nothing is
+    /// applied and no notification happens.
+    pub fn msg_batch(&self, messages: impl IntoIterator<Item = U::Message>) {
+        self.dispatch(
+            messages.into_iter().collect(),
+            self.inner.dispatch_queue.current_id(),
+        );
+    }
+
+    /// This method maps every item to a message with `f` and applies them with `msg_batch`,
+    /// so subscribers are notified once.
+    pub fn dispatch_mapped<T>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        f: impl Fn(T) -> U::Message,
+    ) {
+        self.msg_batch(items.into_iter().map(f));
+    }
+
+    /// This creates a universe for `core` and replays a message log recorded against this core type
+    /// onto it, translating every message with `map`.
+    ///
+    /// It's meant for schema migrations of recorded sessions. Messages that `map` turns into `None`
+    /// are dropped, so the replayed state only reflects the messages that have a counterpart.
+    pub fn replay_mapped<V: AppUniverseCore + 'static>(
+        core: V,
+        msgs: Vec<U::Message>,
+        map: impl Fn(U::Message) -> Option<V::Message>,
+    ) -> AppUniverse<V> {
+        let universe = AppUniverse::new(core);
+        universe.msg_batch(msgs.into_iter().filter_map(map));
+        universe
+    }
+
+    /// This method dispatches one message for `seed` and then one for every item, where each message
+    /// is produced by `step` from the state left behind by the previous one.
+    ///
+    /// Unlike `msg_batch`, every step can read the result of the messages before it. Subscribers are
+    /// still notified once, after the last message has been applied. When this is called from a
+    /// subscriber the whole fold runs after the current notification pass.
+    pub fn dispatch_fold<T: 'static>(
+        &self,
+        seed: T,
+        items: impl IntoIterator<Item = T> + 'static,
+        step: impl Fn(&U, T) -> U::Message + 'static,
+    ) {
+        let producer = Box::new(move |universe: &mut U, dispatcher: &Dispatcher<U>| {
+            let mut applied = 0;
+
+            for item in std::iter::once(seed).chain(items) {
+                let message = step(universe, item);
+                universe.msg_with(message, dispatcher);
+                applied += 1;
+            }
+
+            applied
+        });
+
+        self.enqueue(dispatch::QueuedDispatch::produced(
+            producer,
+            self.inner.dispatch_queue.current_id(),
+        ));
+    }
+
+    /// This method gives `f` mutable access to the state and returns what it returns. The version
+    /// goes up by one and subscribers are notified once, like for a single `msg`.
+    ///
+    /// It's an escape hatch for hot paths where building a message is too costly, the mutation
+    /// counterpart to `read`. Nothing records what `f` did: it bypasses `msg`/`msg_with`, message
+    /// capture and anything else that looks at messages. Prefer `msg` everywhere else.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called from a subscriber (or anything else that runs while a dispatch is being
+    /// applied), since the mutation couldn't be applied right away, and when the universe is closed
+    /// or frozen. Unlike `msg`, this panics on a frozen universe in release builds as well, since
+    /// there is no result to return when the mutation is dropped.
+    pub fn with_mut<R: 'static>(&self, f: impl FnOnce(&mut U) -> R + 'static) -> R {
+        assert!(
+            !self.inner.dispatch_queue.is_draining(),
+            "with_mut can't be called while a dispatch is being applied"
+        );
+        assert!(
+            !self.is_closed(),
+            "with_mut can't be called on a closed universe"
+        );
+        assert!(
+            !self.is_frozen(),
+            "with_mut can't be called on a frozen universe"
+        );
+
+        let result = Rc::new(RefCell::new(None));
+        let slot = result.clone();
+        let producer = Box::new(move |universe: &mut U, _: &Dispatcher<U>| {
+            *slot.borrow_mut() = Some(f(universe));
+            1
+        });
+
+        self.enqueue(dispatch::QueuedDispatch::produced(
+            producer,
+            self.inner.dispatch_queue.current_id(),
+        ));
+
+        let result = result.borrow_mut().take();
+        result.expect("with_mut was not applied")
+    }
+
+    // Queues `messages` as a single dispatch and drains the queue unless that is already
+    // happening further up the stack.
+    fn dispatch(&self, messages: Vec<U::Message>, id: Option<DispatchId>) {
+        self.enqueue(dispatch::QueuedDispatch::new(messages, id));
+    }
+
+    fn enqueue(&self, dispatch: dispatch::QueuedDispatch<U>) {
+        self.enqueue_all(std::iter::once(dispatch));
+    }
+
+    // Queues every dispatch before draining, so that they are ordered by their priorities.
+    fn enqueue_all(&self, dispatches: impl IntoIterator<Item = dispatch::QueuedDispatch<U>>) {
+        if self.inner.closed.get() {
+            return;
+        }
+        if self.inner.frozen.get() {
+            freeze::reject_frozen_dispatch();
+            return;
+        }
+
+        for dispatch in dispatches {
+            #[cfg(feature = "test-utils")]
+            let dispatch = match self.capture(dispatch) {
+                Some(dispatch) => dispatch,
+                None => continue,
+            };
+
+            self.inner.dispatch_queue.push(dispatch);
+        }
+        self.inner
+            .dispatch_queue
+            .drain(|dispatch| self.apply(dispatch));
+    }
+
+    // Applies the queued messages in order and notifies once if anything was applied.
+    fn apply(&self, dispatch: dispatch::QueuedDispatch<U>) {
+        let mut applied = dispatch.messages.len() as u64;
+        let dispatcher = Dispatcher::new();
+
+        {
+            let mut universe = self.inner.universe.borrow_mut();
+
+            #[cfg(feature = "debug-invariant")]
+            self.verify_invariant(&universe);
+
+            let mut recorded_version = self.inner.version.get();
+            let mut observers = self.inner.observers.borrow_mut();
+
+            for message in dispatch.messages {
+                recorded_version += 1;
+                self.record_message(recorded_version, &message);
+
+                let observed = observers.as_ref().map(|observers| observers.copy(&message));
+                universe.msg_with(message, &dispatcher);
+
+                if let (Some(observers), Some(observed)) = (observers.as_mut(), observed) {
+                    observers.apply(observed);
+                }
+            }
+
+            if let Some(producer) = dispatch.producer {
+                applied += producer(&mut universe, &dispatcher);
+            }
+
+            #[cfg(feature = "debug-invariant")]
+            self.record_invariant(&universe);
+        }
+
+        self.inner.version.set(self.inner.version.get() + applied);
+        if applied > 0 {
+            self.inner.dispatch_queue.record_applied();
+        }
+
+        // This runs while the queue is draining, so the follow-ups are picked up right after.
+        let follow_ups = dispatcher.into_follow_ups();
+        if !follow_ups.is_empty() {
+            self.inner.dispatch_queue.push(
+                dispatch::QueuedDispatch::new(follow_ups, dispatch.id)
+                    .with_priority(dispatch.priority),
+            );
+        }
+
+        match dispatch.notify {
+            notification::Notify::IfChanged | notification::Notify::Pending if applied == 0 => {}
+            notification::Notify::IfChanged if !self.state_changed() => {}
+            notification::Notify::Pending => self.inner.notifier.mark_dirty(),
+            notification::Notify::IfChanged | notification::Notify::Always => {
+                self.schedule_notification()
+            }
+            notification::Notify::Flush => {
+                if self.inner.notifier.take_pending() {
+                    self.notify_subscribers();
+                }
+            }
+        }
+    }
+
+    // Notifies subscribers now or later, depending on the notification strategy.
+    fn schedule_notification(&self) {
+        if self.inner.notifier.is_held() {
+            // The last release picks this up.
+            self.inner.notifier.mark_dirty();
+            return;
+        }
+
+        match self.inner.notifier.strategy() {
+            NotificationStrategy::Immediate | NotificationStrategy::OnChange => {
+                // This also covers a notification left pending by a background message.
+                self.inner.notifier.take_pending();
+                self.notify_subscribers()
+            }
+            NotificationStrategy::Manual => {
+                self.inner.notifier.defer();
+            }
+            NotificationStrategy::Microtask => {
+                if self.inner.notifier.defer() {
+                    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+                    {
+                        let universe = self.clone();
+                        notification::queue_microtask(move || universe.flush());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sets the strategy that decides when subscribers are notified.
+    ///
+    /// `NotificationStrategy::OnChange` only compares states if the universe was built with
+    /// `AppUniverseBuilder::notify_on_change`, otherwise it behaves like `Immediate`.
+    pub fn set_notification_strategy(&self, strategy: NotificationStrategy) {
+        self.inner.notifier.set_strategy(strategy);
+    }
+
+    // Asks the change detector whether the state differs from the last notified one. Without the
+    // `OnChange` strategy or a detector every applied message counts as a change, and so does
+    // every message applied while notifications are held back.
+    fn state_changed(&self) -> bool {
+        if self.inner.notifier.is_held()
+            || self.inner.notifier.strategy() != NotificationStrategy::OnChange
+        {
+            return true;
+        }
+
+        match self.inner.change_detector.borrow_mut().as_mut() {
+            Some(detector) => detector(&self.inner.universe.borrow()),
+            None => true,
+        }
+    }
+
+    /// Runs a notification that was deferred by the `Microtask` or `Manual` strategy right away.
+    /// Nothing happens if no notification is pending.
+    ///
+    /// Messages dispatched by subscribers during the flush are deferred again instead of being
+    /// notified re-entrantly.
+    pub fn flush(&self) {
+        self.enqueue(dispatch::QueuedDispatch::flush());
+    }
+
+    // Runs every subscriber and wakes anything waiting for a change.
+    fn notify_subscribers(&self) {
+        let batch = BatchInfo::since(
+            self.inner
+                .notified_version
+                .replace(self.inner.version.get()),
+            self,
+        );
+        self.run_subscribers(batch, |_| true);
+
+        #[cfg(feature = "async")]
+        change_waiter::wake_all(&self.inner.change_waiters, self.inner.version.get());
+    }
+
+    // Runs the subscribers accepted by `filter` and then applies the subscription changes they
+    // made.
+    //
+    // The subscribers are collected first, so they can subscribe, unsubscribe and inspect the
+    // subscriptions while being notified. Subscriptions removed during the pass are skipped.
+    fn run_subscribers(&self, batch: BatchInfo, filter: impl Fn(&Subscription<U>) -> bool) {
+        let pass = NotificationPass::begin(&self.inner.notifying);
+        let subscribers = self.inner.subscriptions.borrow().clone();
+
+        for subscriber in &subscribers {
+            let subscriber = subscriber.borrow();
+            if subscriber.unsubscribed.get() || !filter(&subscriber) {
+                continue;
+            }
+
+            #[cfg(feature = "test-utils")]
+            if let Some(log) = self.inner.notification_log.borrow().as_ref() {
+                let label = match &subscriber.label {
+                    Some(label) => label.clone(),
+                    None => format!("#{}", subscriber.id.0),
+                };
+                log.record(label, self.inner.version.get());
+            }
+
+            let ctx = SubscriberCtx {
+                universe: self,
+                id: subscriber.id,
+                unsubscribed: &subscriber.unsubscribed,
+                batch,
+            };
+
+            #[cfg(feature = "metrics")]
+            let started = self.now();
+
+            (subscriber.callback.borrow_mut())(&ctx);
+
+            #[cfg(feature = "metrics")]
+            if let (Some(started), Some(finished)) = (started, self.now()) {
+                subscriber.timing.record(finished.saturating_sub(started));
+            }
+        }
+        drop(pass);
+
+        self.inner
+            .subscriptions
+            .borrow_mut()
+            .retain(|sub| !sub.borrow().unsubscribed.get());
+
+        let pending = std::mem::take(&mut *self.inner.pending_subscriptions.borrow_mut());
+        if !pending.is_empty() {
+            let mut subscriptions = self.inner.subscriptions.borrow_mut();
+            for subscription in pending {
+                insert_by_priority(&mut subscriptions, subscription);
+            }
+        }
+    }
+
+    /// Closes the universe for good. Messages dispatched after this are dropped, so the state and
+    /// the version stay as they are and subscribers are no longer notified.
+    pub fn close(&self) {
+        self.inner.closed.set(true);
+    }
+
+    /// Returns `true` if the universe has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.closed.get()
+    }
+
+    /// Returns the current version of the state.
+    ///
+    /// The version starts at `0` and goes up by one for every message that is applied,
+    /// so two equal versions always refer to the same state.
+    pub fn version(&self) -> u64 {
+        self.inner.version.get()
+    }
+
+    /// Returns the number of dispatches that applied at least one message so far.
+    ///
+    /// Unlike `version`, a dispatch of several messages (e.g. `msg_batch`) counts once, and
+    /// dispatches that were rolled back by `transaction` still count.
+    pub fn dispatch_count(&self) -> u64 {
+        self.inner.dispatch_queue.applied_count()
+    }
+
+    /// Returns the number of registered subscriptions, including the ones made during the current
+    /// notification pass and excluding the ones removed during it.
+    pub fn subscriber_count(&self) -> usize {
+        let active = self
+            .inner
+            .subscriptions
+            .borrow()
+            .iter()
+            .filter(|sub| !sub.borrow().unsubscribed.get())
+            .count();
+
+        active + self.inner.pending_subscriptions.borrow().len()
+    }
+
+    /// Returns `true` if the state has changed since `version` was observed.
+    pub fn changed_since(&self, version: u64) -> bool {
+        self.inner.version.get() != version
+    }
+
+    /// Returns a `ChangeCursor` positioned at the current version.
+    pub fn change_cursor(&self) -> ChangeCursor {
+        ChangeCursor::at(self.inner.version.get())
+    }
+
+    /// Returns `true` if the state changed since `cursor` last polled, and moves the cursor to the
+    /// current version.
+    ///
+    /// This follows the version counter exactly, so the cursor reports a change whenever the version
+    /// moved, no matter how many messages were applied in between.
+    pub fn poll_changes(&self, cursor: &mut ChangeCursor) -> bool {
+        cursor.advance(self.inner.version.get())
+    }
+
+    /// Acquire read access to the state only if it changed since `cursor` last polled.
+    /// The cursor is moved to the current version like in `poll_changes`.
+    pub fn read_if_changed(&self, cursor: &mut ChangeCursor) -> Option<Ref<'_, U>> {
+        if self.poll_changes(cursor) {
+            Some(self.read())
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "async")]
+    /// Returns a future that resolves with the new version the next time subscribers are notified.
+    ///
+    /// Only notifications that happen after this method is called resolve the future, changes that
+    /// already happened are never reported. Any number of waiters can be pending at once and a single
+    /// notification resolves all of them. Dropping the future before it resolves unregisters it.
+    pub fn wait_for_change(&self) -> impl Future<Output = u64> {
+        change_waiter::WaitForChange::register(&self.inner.change_waiters)
+    }
+
+    /// Returns the cell that holds the state, for wiring the universe into other reactive systems
+    /// that expect to share ownership of it.
+    ///
+    /// This is an advanced escape hatch. Mutating the state through the cell bypasses messages,
+    /// the version and notifications, so subscribers aren't told and `version` keeps reporting the
+    /// old state. Only use it for reads, or dispatch a message afterwards so the universe catches
+    /// up. Holding a borrow of the cell while a message is dispatched panics.
+    pub fn shared_state(&self) -> Rc<RefCell<U>> {
+        self.inner.universe.clone()
+    }
+
+    /// Returns `true` if `self` and `other` are handles to the same universe, e.g. because one is
+    /// a clone of the other.
+    pub fn same_universe(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
     }
 
     /// Acquire read access to the state.
     pub fn read(&self) -> Ref<'_, U> {
-        self.universe.borrow()
+        let state = self.inner.universe.borrow();
+
+        #[cfg(feature = "debug-invariant")]
+        self.verify_invariant(&state);
+
+        state
+    }
+
+    /// Acquire read access to the state like `read`, but returns `UniverseError::AlreadyBorrowed`
+    /// instead of panicking while the state is borrowed mutably, e.g. by a message being applied
+    /// further up the stack.
+    pub fn try_read(&self) -> Result<Ref<'_, U>, UniverseError> {
+        let state = self
+            .inner
+            .universe
+            .try_borrow()
+            .map_err(|_| UniverseError::AlreadyBorrowed)?;
+
+        #[cfg(feature = "debug-invariant")]
+        self.verify_invariant(&state);
+
+        Ok(state)
+    }
+
+    /// Returns a copy of the value `f` selects from the state. The borrow ends before this
+    /// returns, so nothing has to be dropped before the next dispatch.
+    ///
+    /// This is the preferred way to read scalar fields, `read` is for everything that can't be
+    /// copied cheaply.
+    ///
+    /// ```rust
+    /// # use app_universe::{AppUniverse, AppUniverseCore};
+    /// struct Counter { counter: u8 }
+    ///
+    /// impl AppUniverseCore for Counter {
+    ///     type Message = u8;
+    ///     fn msg(&mut self, amount: u8) { self.counter += amount; }
+    /// }
+    ///
+    /// let universe = AppUniverse::new(Counter { counter: 0 });
+    /// universe.msg(2);
+    ///
+    /// let counter = universe.get(|state| state.counter);
+    /// universe.msg(counter);
+    /// assert_eq!(universe.get(|state| state.counter), 4);
+    /// ```
+    pub fn get<T: Copy>(&self, f: impl Fn(&U) -> T) -> T {
+        f(&self.read())
+    }
+
+    /// Runs `f` with the state and returns its result. The borrow ends before this returns, so
+    /// values derived from the state can be computed inline without keeping a `Ref` alive across
+    /// the next dispatch.
+    ///
+    /// This is `get` for results that aren't `Copy`, e.g. a `String` built from several fields.
+    pub fn read_with<R>(&self, f: impl FnOnce(&U) -> R) -> R {
+        f(&self.read())
+    }
+
+    /// Runs `f` with the state and returns its result, or returns `fallback` if the state is
+    /// borrowed mutably right now, e.g. because a message is being applied further up the stack.
+    ///
+    /// Unlike `read`, this never panics because of borrow timing, so glue code that can be called
+    /// from anywhere can degrade gracefully. Getting `fallback` back means the state was
+    /// momentarily inaccessible, not that it's empty.
+    pub fn read_or<R>(&self, fallback: R, f: impl FnOnce(&U) -> R) -> R {
+        match self.inner.universe.try_borrow() {
+            Ok(state) => {
+                #[cfg(feature = "debug-invariant")]
+                self.verify_invariant(&state);
+
+                f(&state)
+            }
+            Err(_) => fallback,
+        }
+    }
+
+    /// Takes the state out of the universe, which only works if this is the last handle to it.
+    ///
+    /// Any other clone of the universe makes this fail, including clones captured by subscribers
+    /// or by pending async dispatches, and the universe is returned unchanged. Weak handles don't
+    /// count. Subscribers are dropped along with the universe once this succeeds.
+    // The universe is returned as is on failure, like `Rc::try_unwrap` does.
+    #[allow(clippy::result_large_err)]
+    pub fn into_inner(self) -> Result<U, AppUniverse<U>> {
+        match Rc::try_unwrap(self.inner) {
+            Ok(inner) => match Rc::try_unwrap(inner.universe) {
+                Ok(universe) => Ok(universe.into_inner()),
+                Err(universe) => Err(AppUniverse {
+                    inner: Rc::new(UniverseInner { universe, ..inner }),
+                }),
+            },
+            Err(inner) => Err(AppUniverse { inner }),
+        }
+    }
+
+    /// Acquire read access to the part of the state picked by `selector`, e.g. a single field.
+    ///
+    /// Read borrows can coexist, so a component can hold on to the slices it needs from separate
+    /// calls instead of going through `read` every time. Like `read`, dispatching while any of
+    /// them is held panics.
+    pub fn split_read<A: ?Sized>(&self, selector: impl FnOnce(&U) -> &A) -> Ref<'_, A> {
+        Ref::map(self.read(), selector)
+    }
+
+    /// Acquire read access to two parts of the state at once, like calling `split_read` twice.
+    pub fn read2<A: ?Sized, B: ?Sized>(
+        &self,
+        selector_a: impl FnOnce(&U) -> &A,
+        selector_b: impl FnOnce(&U) -> &B,
+    ) -> (Ref<'_, A>, Ref<'_, B>) {
+        (self.split_read(selector_a), self.split_read(selector_b))
     }
 
     /// This function takes a subscriber function that runs anytime the state changes.
     ///
     /// A subscriber function `subscriber_fn` is a function that will be called whenever state changes and it will pass in the updated state
+    ///
+    /// Subscribers that dispatch should use `subscribe_weak_self` instead of capturing a clone of
+    /// the universe, which keeps the universe alive forever.
+    ///
+    /// Subscribing from inside a subscriber is fine, the new subscriber is first notified of the
+    /// next change.
     pub fn subscribe(
+        &mut self,
+        subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        self.subscribe_boxed(Box::new(subscriber_fn))
+    }
+
+    /// This function takes a subscriber function like `subscribe`, for callers that already hold
+    /// a boxed one, e.g. one picked at runtime.
+    pub fn subscribe_boxed(
         &mut self,
         subscriber_fn: Box<dyn FnMut(AppUniverse<U>)>,
     ) -> UniverseSubscription<U> {
-        let subscription = Rc::new(RefCell::new(Subscription(subscriber_fn)));
+        let id = self.next_subscription_id();
+        self.add_subscription(Subscription::new(id, subscriber_fn))
+    }
+
+    /// This function takes a `map` that derives a value from the state and a `callback` that runs
+    /// with that value anytime the state changes.
+    ///
+    /// The value is computed after every change and passed to `callback` without comparing it to
+    /// the previous one, so `callback` runs on every notification even when the value is the same.
+    /// This only saves deriving the value in the callback, it doesn't filter notifications.
+    pub fn subscribe_map<T: 'static>(
+        &mut self,
+        map: impl Fn(&U) -> T + 'static,
+        mut callback: impl FnMut(T) + 'static,
+    ) -> UniverseSubscription<U> {
+        self.subscribe(move |universe| {
+            let value = map(&universe.read());
+            callback(value);
+        })
+    }
+
+    /// This function takes a subscriber function that only runs on every `n`th notification, e.g.
+    /// to log every 100th change or to update a coarse progress bar.
+    ///
+    /// Every subscription counts its own notifications, starting when it subscribes. The
+    /// notifications in between are skipped entirely, they aren't batched up for the next run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn subscribe_every(
+        &mut self,
+        n: usize,
+        mut subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        assert!(n > 0, "subscribe_every needs n to be at least 1");
+
+        let mut count = 0;
+        self.subscribe(move |universe| {
+            count += 1;
+            if count == n {
+                count = 0;
+                subscriber_fn(universe);
+            }
+        })
+    }
+
+    /// This function takes a subscriber function that only runs when `predicate` returns `true` for
+    /// the state after a change, so the filtering doesn't have to be repeated in every subscriber.
+    pub fn subscribe_when(
+        &mut self,
+        predicate: impl Fn(&U) -> bool + 'static,
+        mut subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        self.subscribe(move |universe| {
+            if predicate(&universe.read()) {
+                subscriber_fn(universe);
+            }
+        })
+    }
+
+    /// This function takes a subscriber function like `subscribe`, for subscribers that are meant
+    /// to stay registered for as long as the universe exists.
+    ///
+    /// No handle is returned, so the subscriber can't be unsubscribed later.
+    pub fn subscribe_forever(&mut self, subscriber_fn: impl FnMut(AppUniverse<U>) + 'static) {
+        let _ = self.subscribe(subscriber_fn);
+    }
+
+    /// This function subscribes `subscriber_fn` under `key` unless a subscription with that key
+    /// already exists, in which case nothing is added.
+    ///
+    /// This is meant for idempotent setup code that has to make sure exactly one such subscriber is
+    /// registered. A subscription is returned only if one was actually added.
+    pub fn subscribe_unique(
+        &mut self,
+        key: impl Into<String>,
+        subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+    ) -> Option<UniverseSubscription<U>> {
+        let key = key.into();
+
+        if self.has_subscription_with_key(&key) {
+            return None;
+        }
+
+        let id = self.next_subscription_id();
+        let mut subscription = Subscription::new(id, Box::new(subscriber_fn));
+        subscription.key = Some(key);
+
+        Some(self.add_subscription(subscription))
+    }
+
+    // Looks at the subscriptions made during the current notification pass as well, and ignores
+    // the ones removed during it.
+    fn has_subscription_with_key(&self, key: &str) -> bool {
+        let has_key = |sub: &UniverseSubscriptionParameter<U>| {
+            let sub = sub.borrow();
+            !sub.unsubscribed.get() && sub.key.as_deref() == Some(key)
+        };
+
+        self.inner.subscriptions.borrow().iter().any(has_key)
+            || self
+                .inner
+                .pending_subscriptions
+                .borrow()
+                .iter()
+                .any(has_key)
+    }
+
+    /// This function subscribes `subscriber_fn` under `key`, replacing the subscription that
+    /// already had that key, if any.
+    ///
+    /// This is meant for glue code that can't hold on to the returned handle: the subscription can
+    /// be removed with `unsubscribe_key` instead.
+    pub fn subscribe_keyed(
+        &mut self,
+        key: impl Into<String>,
+        subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        let key = key.into();
+        self.unsubscribe_key(&key);
+
+        let id = self.next_subscription_id();
+        let mut subscription = Subscription::new(id, Box::new(subscriber_fn));
+        subscription.key = Some(key);
+
+        self.add_subscription(subscription)
+    }
+
+    /// This function removes the subscription with `key`, made with `subscribe_keyed` or
+    /// `subscribe_unique`, and returns whether there was one.
+    pub fn unsubscribe_key(&mut self, key: &str) -> bool {
+        self.remove_subscriptions(|sub| sub.borrow().key.as_deref() == Some(key))
+    }
+
+    /// This function takes a subscriber function like `subscribe` and gives the subscription a
+    /// `label`, which identifies it in logs and diagnostics. Labels don't have to be unique.
+    pub fn subscribe_labeled(
+        &mut self,
+        label: impl Into<String>,
+        subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        let id = self.next_subscription_id();
+        let mut subscription = Subscription::new(id, Box::new(subscriber_fn));
+        subscription.label = Some(label.into());
+
+        self.add_subscription(subscription)
+    }
+
+    fn next_subscription_id(&self) -> SubscriptionId {
+        let id = self.inner.next_subscription_id.get() + 1;
+        self.inner.next_subscription_id.set(id);
+        SubscriptionId(id)
+    }
+
+    // Subscriptions added while subscribers are being notified are only notified from the next
+    // notification on.
+    fn add_subscription(&mut self, subscription: Subscription<U>) -> UniverseSubscription<U> {
+        let subscription = Rc::new(RefCell::new(subscription));
+
+        #[cfg(any(feature = "test-utils", feature = "devtools"))]
+        {
+            subscription.borrow_mut().created_version = self.inner.version.get();
+        }
 
         let universe_subscription = UniverseSubscription(subscription.clone());
 
-        self.subscriptions.borrow_mut().push(subscription.clone());
+        if self.inner.notifying.get() {
+            // Subscribers are being notified, the subscription joins once they are done.
+            self.inner
+                .pending_subscriptions
+                .borrow_mut()
+                .push(subscription);
+        } else {
+            insert_by_priority(&mut self.inner.subscriptions.borrow_mut(), subscription);
+        }
 
         universe_subscription
     }
 
-    /// This function takes a subscription and removes the subscriber function so that it is no longer gets called whenever state changes
-    pub fn unsubscribe(&mut self, subscription: UniverseSubscription<U>) -> Result<(), &str> {
-        let sub_len_before = self.subscriptions.borrow().len();
+    /// This function takes a subscriber function like `subscribe`, which is notified according to
+    /// `priority` instead of the default priority.
+    pub fn subscribe_with_priority(
+        &mut self,
+        subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+        priority: SubscriberPriority,
+    ) -> UniverseSubscription<U> {
+        let id = self.next_subscription_id();
+        let subscription = Subscription::new(id, Box::new(subscriber_fn));
+        subscription.priority.set(priority);
 
-        self.subscriptions
-            .borrow_mut()
-            .retain(|sub| !Rc::ptr_eq(sub, &subscription.0));
+        self.add_subscription(subscription)
+    }
+
+    /// This function changes the priority of an existing subscription.
+    ///
+    /// The subscription moves to its new place in the notification order straight away. Within its
+    /// new priority it keeps its original registration order. A subscription made during the
+    /// current notification pass joins at its new place once the pass is over.
+    ///
+    /// Returns an error if the subscription isn't registered, including when it was unsubscribed
+    /// during the current notification pass.
+    pub fn reprioritize(
+        &mut self,
+        subscription: &UniverseSubscription<U>,
+        new_priority: SubscriberPriority,
+    ) -> Result<(), UniverseError> {
+        let is_registered = |sub: &UniverseSubscriptionParameter<U>| {
+            Rc::ptr_eq(sub, &subscription.0) && !sub.borrow().unsubscribed.get()
+        };
 
-        let sub_len_after = self.subscriptions.borrow().len();
+        let mut subscriptions = self.inner.subscriptions.borrow_mut();
+        if subscriptions.iter().any(is_registered) {
+            subscription.0.borrow().priority.set(new_priority);
+            subscriptions.sort_by_key(|sub| {
+                let sub = sub.borrow();
+                (sub.priority.get(), sub.id)
+            });
+        } else if self
+            .inner
+            .pending_subscriptions
+            .borrow()
+            .iter()
+            .any(is_registered)
+        {
+            // It's inserted by its priority once the current notification pass is over.
+            subscription.0.borrow().priority.set(new_priority);
+        } else {
+            return Err(UniverseError::SubscriptionNotFound);
+        }
 
-        if sub_len_before != sub_len_after {
-            return Ok(());
+        Ok(())
+    }
+
+    /// This function takes a subscription and removes the subscriber function so that it is no longer gets called whenever state changes
+    pub fn unsubscribe(
+        &mut self,
+        subscription: UniverseSubscription<U>,
+    ) -> Result<(), UniverseError> {
+        if self.try_unsubscribe(subscription) {
+            Ok(())
         } else {
-            return Err("Subscription not found");
+            Err(UniverseError::SubscriptionNotFound)
+        }
+    }
+
+    /// This function removes a subscription like `unsubscribe`, but only reports whether it was
+    /// still registered instead of failing when it wasn't.
+    pub fn try_unsubscribe(&mut self, subscription: UniverseSubscription<U>) -> bool {
+        self.remove_subscriptions(|sub| Rc::ptr_eq(sub, &subscription.0))
+    }
+
+    // Removes the subscriptions matched by `matches` and returns whether there were any. During a
+    // notification pass they are only marked as unsubscribed, so the pass skips them and drops
+    // them once it's over.
+    fn remove_subscriptions(
+        &self,
+        matches: impl Fn(&UniverseSubscriptionParameter<U>) -> bool,
+    ) -> bool {
+        let mut pending = self.inner.pending_subscriptions.borrow_mut();
+        let pending_len_before = pending.len();
+        pending.retain(|sub| !matches(sub));
+        let mut removed = pending.len() != pending_len_before;
+        drop(pending);
+
+        for sub in self.inner.subscriptions.borrow().iter() {
+            if matches(sub) && !sub.borrow().unsubscribed.replace(true) {
+                removed = true;
+            }
         }
+
+        if !self.inner.notifying.get() {
+            self.inner
+                .subscriptions
+                .borrow_mut()
+                .retain(|sub| !sub.borrow().unsubscribed.get());
+        }
+
+        removed
+    }
+
+    #[cfg(feature = "test-utils")]
+    /// This function is used in tests to mutate state directly so that preconditions can be
+    /// arranged without dispatching a long sequence of messages.
+    ///
+    /// It does not fire subscribers and it does not bump the version, so the universe behaves as if
+    /// it had been created with the mutated state. It is only available with the `test-utils`
+    /// feature so that `msg` stays the only way to mutate state in regular builds.
+    pub fn test_set_state(&self, f: impl FnOnce(&mut U)) {
+        let mut universe = self.inner.universe.borrow_mut();
+        f(&mut universe);
+
+        #[cfg(feature = "debug-invariant")]
+        self.record_invariant(&universe);
     }
 
     #[cfg(feature = "test-utils")]
     /// This function is used in tests to read subscriptions
     pub fn read_subscriptions(&self) -> Ref<'_, Vec<UniverseSubscriptionParameter<U>>> {
-        self.subscriptions.borrow()
+        self.inner.subscriptions.borrow()
+    }
+}
+
+// Marks a notification pass for as long as it's alive, and ends it even if a subscriber panics.
+struct NotificationPass<'a> {
+    notifying: &'a Cell<bool>,
+    was_notifying: bool,
+}
+
+impl<'a> NotificationPass<'a> {
+    fn begin(notifying: &'a Cell<bool>) -> Self {
+        NotificationPass {
+            notifying,
+            was_notifying: notifying.replace(true),
+        }
+    }
+}
+
+impl Drop for NotificationPass<'_> {
+    fn drop(&mut self) {
+        self.notifying.set(self.was_notifying);
+    }
+}
+
+// Subscriptions are kept sorted by priority, and by registration order within a priority.
+fn insert_by_priority<U: AppUniverseCore>(
+    subscriptions: &mut Vec<UniverseSubscriptionParameter<U>>,
+    subscription: UniverseSubscriptionParameter<U>,
+) {
+    let priority = subscription.borrow().priority.get();
+    let position = subscriptions.partition_point(|sub| sub.borrow().priority.get() <= priority);
+    subscriptions.insert(position, subscription);
+}
+
+/// Extending a universe dispatches every message with `msg_batch`, so subscribers are
+/// notified once after the whole iterator has been consumed.
+impl<U: AppUniverseCore + 'static> Extend<U::Message> for AppUniverse<U> {
+    fn extend<I: IntoIterator<Item = U::Message>>(&mut self, messages: I) {
+        self.msg_batch(messages);
     }
 }
 
 impl<W: AppUniverseCore> Clone for AppUniverse<W> {
     fn clone(&self) -> Self {
         AppUniverse {
-            universe: self.universe.clone(),
-            subscriptions: self.subscriptions.clone(),
+            inner: self.inner.clone(),
         }
     }
 }
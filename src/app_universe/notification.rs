@@ -0,0 +1,164 @@
+use std::cell::Cell;
+
+/// The `NotificationStrategy` decides whether and when subscribers are notified after state changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotificationStrategy {
+    /// Subscribers are notified synchronously after every dispatch that applied a message. This is
+    /// the default.
+    ///
+    /// It's the simplest to reason about, but subscribers also run for messages that left the state
+    /// as it was.
+    #[default]
+    Immediate,
+    /// Like `Immediate`, but subscribers are only notified if the state differs from the state of
+    /// the previous notification.
+    ///
+    /// Comparing needs a snapshot of the state, so this requires `U: PartialEq + Clone` and is
+    /// selected with `AppUniverseBuilder::notify_on_change`. Every dispatch pays for a comparison,
+    /// and every notification for a clone of the state.
+    OnChange,
+    /// Dispatches only mark the universe as dirty and a single notification runs later, so any
+    /// number of dispatches in the same synchronous burst cause one notification.
+    ///
+    /// With the `wasm` feature on `wasm32` targets the notification runs in a microtask
+    /// (`queueMicrotask`). Everywhere else nothing is scheduled and `AppUniverse::flush` has to be
+    /// called explicitly.
+    /// `read` always reflects the applied state, even before the notification has run.
+    Microtask,
+    /// Dispatches only mark the universe as dirty and subscribers are notified when
+    /// `AppUniverse::flush` is called, on every target.
+    ///
+    /// This gives full control over when subscribers run (e.g. once per frame), at the cost of
+    /// having to remember to flush.
+    Manual,
+}
+
+/// The `MessagePriority` decides whether a message notifies subscribers right away or waits for
+/// the next message that does. It's passed to `AppUniverse::msg_with_notification_priority`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessagePriority {
+    /// User-visible changes, which notify according to the notification strategy. This is the
+    /// priority of every other way of dispatching.
+    #[default]
+    Interactive,
+    /// Background updates, which are applied right away but only mark a notification as pending.
+    ///
+    /// The pending notification runs with the next `Interactive` message that notifies, or when
+    /// `AppUniverse::flush` is called, whichever happens first.
+    Background,
+}
+
+// When a queued dispatch should notify subscribers.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Notify {
+    // Notify according to the strategy if any message was applied.
+    IfChanged,
+    // Notify according to the strategy even if no message was applied.
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    Always,
+    // Run a pending deferred notification right away.
+    Flush,
+    // Only mark a notification as pending if any message was applied, without scheduling it.
+    Pending,
+}
+
+#[derive(Default)]
+pub(crate) struct Notifier {
+    strategy: Cell<NotificationStrategy>,
+    dirty: Cell<bool>,
+    scheduled: Cell<bool>,
+    // The number of pauses, updates and transactions currently holding back notifications.
+    holds: Cell<u32>,
+    // Whether a notification was already deferred when the first hold started.
+    held_pending: Cell<bool>,
+    paused: Cell<bool>,
+}
+
+impl Notifier {
+    pub(crate) fn strategy(&self) -> NotificationStrategy {
+        self.strategy.get()
+    }
+
+    pub(crate) fn set_strategy(&self, strategy: NotificationStrategy) {
+        self.strategy.set(strategy);
+    }
+
+    // Marks a deferred notification as pending. Returns `true` if a flush has to be scheduled.
+    pub(crate) fn defer(&self) -> bool {
+        self.dirty.set(true);
+        !self.scheduled.replace(true)
+    }
+
+    // Marks a notification as pending without scheduling a flush, so the next notification or
+    // flush picks it up.
+    pub(crate) fn mark_dirty(&self) {
+        self.dirty.set(true);
+    }
+
+    // Holds back notifications until the matching `release`. Holds nest and can be released in
+    // any order.
+    pub(crate) fn hold(&self) {
+        if self.holds.get() == 0 {
+            // A notification that was already deferred stays deferred, no matter how this ends.
+            self.held_pending.set(self.take_pending());
+        }
+        self.holds.set(self.holds.get() + 1);
+    }
+
+    // Ends a hold. Returns `true` if it was the last one and anything was dispatched while
+    // notifications were held back.
+    pub(crate) fn release(&self) -> bool {
+        let holds = self.holds.get() - 1;
+        self.holds.set(holds);
+        if holds > 0 {
+            return false;
+        }
+
+        let changed = self.take_pending();
+        if self.held_pending.replace(false) {
+            self.defer();
+        }
+        changed
+    }
+
+    pub(crate) fn is_held(&self) -> bool {
+        self.holds.get() > 0
+    }
+
+    // Marks notifications as paused. Returns `false` if they already were.
+    pub(crate) fn pause(&self) -> bool {
+        !self.paused.replace(true)
+    }
+
+    // Marks notifications as no longer paused. Returns `false` if they weren't.
+    pub(crate) fn resume(&self) -> bool {
+        self.paused.replace(false)
+    }
+
+    // Clears the pending notification. Returns `true` if there was one.
+    pub(crate) fn take_pending(&self) -> bool {
+        self.scheduled.set(false);
+        self.dirty.replace(false)
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod microtask {
+    use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsValue};
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_name = queueMicrotask)]
+        fn queue_microtask_js(callback: &JsValue);
+    }
+
+    // Calling into JavaScript panics on native targets, where deferred notifications wait for an
+    // explicit flush instead.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pub(crate) fn queue_microtask(f: impl FnOnce() + 'static) {
+        queue_microtask_js(&Closure::once_into_js(f));
+    }
+}
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub(crate) use microtask::queue_microtask;
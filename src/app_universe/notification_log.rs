@@ -0,0 +1,70 @@
+use super::{AppUniverse, AppUniverseCore};
+use std::{cell::RefCell, fmt::Write, rc::Rc};
+
+/// A single subscriber invocation captured by a `NotificationLog`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubscriberInvocation {
+    /// The label of the subscription, or `#<id>` for subscriptions without one.
+    pub subscription_label: String,
+    /// The version the subscriber observed.
+    pub version: u64,
+    /// The position of this invocation in the log, starting at `0`.
+    pub ordinal: u64,
+}
+
+/// The `NotificationLog` records every subscriber invocation of a universe while recording is on.
+///
+/// Subscribers always run in a fixed order (by priority, then by registration order), so the log of
+/// a test is the same on every run and can be compared against a snapshot with `render`.
+/// Cloning the log is cheap and all clones share the same entries.
+#[derive(Clone, Default)]
+pub struct NotificationLog(Rc<RefCell<Vec<SubscriberInvocation>>>);
+
+impl NotificationLog {
+    /// Returns a copy of the invocations recorded so far.
+    pub fn entries(&self) -> Vec<SubscriberInvocation> {
+        self.0.borrow().clone()
+    }
+
+    /// Renders the log to a stable string with one `ordinal version label` line per invocation.
+    pub fn render(&self) -> String {
+        let mut rendered = String::new();
+
+        for invocation in self.0.borrow().iter() {
+            let _ = writeln!(
+                rendered,
+                "{} v{} {}",
+                invocation.ordinal, invocation.version, invocation.subscription_label
+            );
+        }
+
+        rendered
+    }
+
+    pub(crate) fn record(&self, subscription_label: String, version: u64) {
+        let mut entries = self.0.borrow_mut();
+        let ordinal = entries.len() as u64;
+
+        entries.push(SubscriberInvocation {
+            subscription_label,
+            version,
+            ordinal,
+        });
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Starts recording subscriber invocations into a new `NotificationLog`, which is returned.
+    /// Calling this again replaces the previous log.
+    pub fn start_recording(&self) -> NotificationLog {
+        let log = NotificationLog::default();
+        *self.inner.notification_log.borrow_mut() = Some(log.clone());
+        log
+    }
+
+    /// Stops recording subscriber invocations. Logs that were handed out keep their entries.
+    pub fn stop_recording(&self) {
+        self.inner.notification_log.borrow_mut().take();
+    }
+}
@@ -0,0 +1,49 @@
+use super::{AppUniverse, AppUniverseCore, SubscriptionGuard};
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+/// An `Observation` keeps the binding created by `AppUniverse::observe` alive.
+///
+/// Dropping it removes its subscription from the universe, so `on_change` doesn't run anymore.
+#[must_use = "dropping the observation unsubscribes it right away"]
+pub struct Observation<T> {
+    value: Rc<RefCell<T>>,
+    // The `SubscriptionGuard` of the binding, boxed so the universe doesn't show up in the type.
+    _guard: Box<dyn Any>,
+}
+
+impl<T: Clone> Observation<T> {
+    /// Returns the value that was selected last.
+    pub fn current(&self) -> T {
+        self.value.borrow().clone()
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function binds `on_change` to a value picked from the state by `select`, for as long as
+    /// the returned `Observation` lives.
+    ///
+    /// The value is selected and passed to `on_change` right away, and again after every
+    /// notification, without comparing it to the previous one. Use `subscribe_selector` to only run
+    /// when the value actually changed.
+    pub fn observe<T: Clone + 'static>(
+        &mut self,
+        select: impl Fn(&U) -> T + 'static,
+        mut on_change: impl FnMut(&T) + 'static,
+    ) -> Observation<T> {
+        let initial = select(&self.read());
+        on_change(&initial);
+        let value = Rc::new(RefCell::new(initial));
+
+        let observed = value.clone();
+        let subscription = self.subscribe(move |universe| {
+            let selected = select(&universe.read());
+            on_change(&selected);
+            *observed.borrow_mut() = selected;
+        });
+
+        Observation {
+            value,
+            _guard: Box::new(SubscriptionGuard::new(self, subscription)),
+        }
+    }
+}
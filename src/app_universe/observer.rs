@@ -0,0 +1,83 @@
+use super::{AppUniverse, AppUniverseCore};
+use std::cell::{Ref, RefCell};
+use std::rc::Rc;
+
+type ObserverFn<M> = Box<dyn FnMut(M)>;
+
+// The observer cores of a universe, which every applied message is fanned out to.
+pub(crate) struct Observers<U: AppUniverseCore> {
+    clone: fn(&U::Message) -> U::Message,
+    cores: Vec<ObserverFn<U::Message>>,
+}
+
+impl<U: AppUniverseCore> Observers<U> {
+    pub(crate) fn copy(&self, message: &U::Message) -> U::Message {
+        (self.clone)(message)
+    }
+
+    // Applies `message` to every observer core, in registration order.
+    pub(crate) fn apply(&mut self, message: U::Message) {
+        let clone = self.clone;
+        if let Some((last, rest)) = self.cores.split_last_mut() {
+            for core in rest {
+                core(clone(&message));
+            }
+            last(message);
+        }
+    }
+}
+
+/// An `ObserverCore` is the handle to a core registered with
+/// `AppUniverse::register_observer_core`, through which its state is read.
+///
+/// Cloning it is cheap, and all clones refer to the same core.
+pub struct ObserverCore<C>(Rc<RefCell<C>>);
+
+impl<C> ObserverCore<C> {
+    /// Acquire read access to the state of the observer core.
+    pub fn read(&self) -> Ref<'_, C> {
+        self.0.borrow()
+    }
+}
+
+impl<C> Clone for ObserverCore<C> {
+    fn clone(&self) -> Self {
+        ObserverCore(self.0.clone())
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U>
+where
+    U::Message: Clone,
+{
+    /// Registers `core` as an observer, which every message applied from now on is applied to as
+    /// well, so a single dispatch drives several independent cores like an event bus.
+    ///
+    /// Every message is applied to the core of the universe first and then to each observer core,
+    /// in the order they were registered, each receiving its own clone. Observer cores receive
+    /// messages through `msg`, so they can't dispatch follow-ups, and messages produced from the
+    /// state, like the ones of `dispatch_fold`, only reach the core of the universe. Subscribers
+    /// are notified as usual once everything is applied.
+    pub fn register_observer_core<C>(&self, core: C) -> ObserverCore<C>
+    where
+        C: AppUniverseCore<Message = U::Message> + 'static,
+    {
+        let core = Rc::new(RefCell::new(core));
+        let observer = {
+            let core = core.clone();
+            Box::new(move |message| core.borrow_mut().msg(message))
+        };
+
+        self.inner
+            .observers
+            .borrow_mut()
+            .get_or_insert_with(|| Observers {
+                clone: U::Message::clone,
+                cores: vec![],
+            })
+            .cores
+            .push(observer);
+
+        ObserverCore(core)
+    }
+}
@@ -0,0 +1,36 @@
+use super::{AppUniverse, AppUniverseCore, SubscriberCtx, UniverseSubscription};
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function takes a subscriber function that runs the next time the state changes, after
+    /// which the subscription removes itself.
+    pub fn subscribe_once(
+        &mut self,
+        subscriber_fn: impl FnOnce(AppUniverse<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        self.subscribe_once_when(|_| true, subscriber_fn)
+    }
+
+    /// This function takes a subscriber function that runs the first time `predicate` returns
+    /// `true` for the state after a change, e.g. to wait for a login flag to flip, after which the
+    /// subscription removes itself.
+    ///
+    /// `predicate` is only checked after changes, not for the state at the time of subscribing.
+    pub fn subscribe_once_when(
+        &mut self,
+        predicate: impl Fn(&U) -> bool + 'static,
+        subscriber_fn: impl FnOnce(AppUniverse<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        let mut subscriber_fn = Some(subscriber_fn);
+
+        self.subscribe_with_ctx(Box::new(move |ctx: &SubscriberCtx<'_, U>| {
+            if !predicate(&ctx.read()) {
+                return;
+            }
+
+            if let Some(subscriber_fn) = subscriber_fn.take() {
+                ctx.unsubscribe_self();
+                subscriber_fn(ctx.universe.clone());
+            }
+        }))
+    }
+}
@@ -0,0 +1,150 @@
+//! Saving the state to an asynchronous storage and restoring it on the next start.
+//!
+//! `AppUniverse::persist_async` writes a snapshot to an `AsyncStorage` after every notification
+//! without blocking dispatch, and `AppUniverse::restore` creates a universe from the last saved
+//! one. With the `indexeddb-storage` feature, `IndexedDbStorage` is an `AsyncStorage` for the
+//! browser.
+
+use super::{AppUniverse, AppUniverseCore, UniverseError, UniverseSubscription};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{cell::RefCell, fmt, future::Future, pin::Pin, rc::Rc};
+
+/// A boxed future returned by an `AsyncStorage`.
+pub type StorageFuture<T> = Pin<Box<dyn Future<Output = Result<T, StorageError>>>>;
+
+/// An `AsyncStorage` saves and loads snapshots of the state without blocking dispatch.
+///
+/// Snapshots are the payloads produced by `AppUniverse::dehydrate`.
+pub trait AsyncStorage {
+    /// Saves `snapshot`, replacing the one saved before.
+    fn save(&self, snapshot: String) -> StorageFuture<()>;
+
+    /// Loads the latest snapshot, or `None` if nothing has been saved yet.
+    fn load(&self) -> StorageFuture<Option<String>>;
+}
+
+/// The error returned by an `AsyncStorage`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    /// The storage ran out of space.
+    QuotaExceeded,
+    /// Any other failure of the storage, described by the message.
+    Backend(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::QuotaExceeded => write!(f, "Storage quota exceeded"),
+            StorageError::Backend(message) => write!(f, "Storage failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+pub(crate) type StorageErrorHandler = Box<dyn FnMut(StorageError)>;
+
+// The handler every universe starts with.
+pub(crate) fn log_storage_error(error: StorageError) {
+    log::error!("Failed to persist state: {}", error);
+}
+
+// The snapshot waiting to be saved and whether a save is running. Snapshots that arrive while a
+// save is running replace each other, so only the newest one is written next.
+#[derive(Default)]
+struct PendingSaves {
+    snapshot: Option<String>,
+    saving: bool,
+}
+
+impl<U> AppUniverse<U>
+where
+    U: AppUniverseCore + Serialize + DeserializeOwned + 'static,
+{
+    /// Creates a universe from the snapshot in `storage`, falling back to `core_fallback` when
+    /// nothing has been saved yet or the snapshot can't be hydrated.
+    ///
+    /// Errors are only returned when the storage itself fails to load.
+    pub async fn restore(
+        storage: &impl AsyncStorage,
+        core_fallback: U,
+    ) -> Result<AppUniverse<U>, StorageError> {
+        let snapshot = storage.load().await?;
+        Ok(AppUniverse::hydrate_into(
+            core_fallback,
+            snapshot.as_deref(),
+        ))
+    }
+
+    /// Saves a snapshot to `storage` every time state changes.
+    ///
+    /// Saves run on the spawner set with `set_spawner`, so dispatch never waits for them. While a
+    /// save is running, newer snapshots replace each other and only the newest one is saved after
+    /// it. Errors are passed to the handler set with `set_storage_error_handler`.
+    pub fn persist_async(
+        &mut self,
+        storage: impl AsyncStorage + 'static,
+    ) -> Result<UniverseSubscription<U>, UniverseError> {
+        self.spawner()?;
+
+        let storage = Rc::new(storage);
+        let pending = Rc::new(RefCell::new(PendingSaves::default()));
+
+        Ok(self.subscribe(move |universe| {
+            let snapshot = match universe.dehydrate() {
+                Ok(snapshot) => snapshot,
+                Err(error) => {
+                    universe.report_storage_error(StorageError::Backend(error.to_string()));
+                    return;
+                }
+            };
+
+            let already_saving = {
+                let mut saves = pending.borrow_mut();
+                saves.snapshot = Some(snapshot);
+                std::mem::replace(&mut saves.saving, true)
+            };
+            if already_saving {
+                return;
+            }
+
+            let storage = storage.clone();
+            let saves = pending.clone();
+            let writer = universe.clone();
+            let spawned = universe.spawner().map(|spawner| {
+                spawner.spawn(Box::pin(async move {
+                    loop {
+                        let snapshot = saves.borrow_mut().snapshot.take();
+                        let snapshot = match snapshot {
+                            Some(snapshot) => snapshot,
+                            None => break,
+                        };
+
+                        if let Err(error) = storage.save(snapshot).await {
+                            writer.report_storage_error(error);
+                        }
+                    }
+
+                    saves.borrow_mut().saving = false;
+                }))
+            });
+
+            if spawned.is_err() {
+                pending.borrow_mut().saving = false;
+            }
+        }))
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Sets the handler that receives the errors of `persist_async`. By default errors are logged
+    /// with `log::error!`.
+    pub fn set_storage_error_handler(&self, handler: impl FnMut(StorageError) + 'static) {
+        *self.inner.storage_error_handler.borrow_mut() = Box::new(handler);
+    }
+
+    fn report_storage_error(&self, error: StorageError) {
+        (self.inner.storage_error_handler.borrow_mut())(error);
+    }
+}
@@ -0,0 +1,23 @@
+use super::{AppUniverse, AppUniverseCore, UniverseSubscription};
+
+impl<U: AppUniverseCore + Clone + 'static> AppUniverse<U> {
+    /// This function takes a subscriber function that receives the state of the previous
+    /// notification and the current state, e.g. to update only the parts of a view that changed.
+    ///
+    /// The first call receives the state from when the subscription was made as the previous one.
+    /// A copy of the state is cloned for the subscription on every notification, so this costs a
+    /// clone of `U` per change. Several messages applied with one notification (e.g. by
+    /// `msg_batch`) show up as a single step.
+    pub fn subscribe_with_previous(
+        &mut self,
+        mut subscriber_fn: impl FnMut(&U, &U) + 'static,
+    ) -> UniverseSubscription<U> {
+        let mut previous = self.read().clone();
+
+        self.subscribe(move |universe| {
+            let current = universe.read().clone();
+            subscriber_fn(&previous, &current);
+            previous = current;
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use super::AppUniverseCore;
+
+/// The `PureUniverseCore` trait is an alternative to `AppUniverseCore` for cores written as pure
+/// reducers, which return the next state instead of mutating the current one.
+///
+/// Every `PureUniverseCore` is an `AppUniverseCore` whose `msg` replaces the state with the result
+/// of `reduce`, so `AppUniverse` drives both styles the same way.
+///
+/// Pure reducers are easy to test, but every message builds a whole new state and drops the old
+/// one. That's cheap for small states or persistent data structures (e.g. the `im` crate) that
+/// share everything that didn't change, and can be costly for large states that are cloned
+/// field by field.
+pub trait PureUniverseCore: Sized {
+    /// The messages the reducer handles.
+    type Message;
+
+    /// Returns the state that results from applying `message` to `self`.
+    fn reduce(&self, message: Self::Message) -> Self;
+}
+
+impl<T: PureUniverseCore> AppUniverseCore for T {
+    type Message = T::Message;
+
+    fn msg(&mut self, message: Self::Message) {
+        *self = self.reduce(message);
+    }
+}
@@ -0,0 +1,63 @@
+use super::{AppUniverse, AppUniverseCore};
+use std::{cell::Ref, ops::Deref};
+
+/// A `ReadCow` is either a read borrow of the state or an owned copy of it, like a `Cow`.
+///
+/// The state lives in a `RefCell`, so a borrowed `ReadCow` holds the `Ref` guard instead of a plain
+/// reference. Dispatching while it's held panics, just like holding on to `read`.
+pub enum ReadCow<'a, U> {
+    /// A read borrow of the state in the universe.
+    Borrowed(Ref<'a, U>),
+    /// A copy of the state that no longer borrows the universe.
+    Owned(U),
+}
+
+impl<U: Clone> ReadCow<'_, U> {
+    /// Returns the owned state, cloning it if it's still borrowed.
+    pub fn into_owned(self) -> U {
+        match self {
+            ReadCow::Borrowed(state) => state.clone(),
+            ReadCow::Owned(state) => state,
+        }
+    }
+
+    /// Returns a mutable reference to an owned copy of the state, cloning it and releasing the
+    /// borrow first if it's still borrowed. Changes to the copy never reach the universe.
+    pub fn to_mut(&mut self) -> &mut U {
+        if let ReadCow::Borrowed(state) = self {
+            *self = ReadCow::Owned(state.clone());
+        }
+
+        match self {
+            ReadCow::Owned(state) => state,
+            ReadCow::Borrowed(_) => unreachable!(),
+        }
+    }
+
+    /// Returns `true` if the state is still borrowed from the universe.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, ReadCow::Borrowed(_))
+    }
+}
+
+impl<U> Deref for ReadCow<'_, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        match self {
+            ReadCow::Borrowed(state) => state,
+            ReadCow::Owned(state) => state,
+        }
+    }
+}
+
+impl<U: AppUniverseCore + Clone + 'static> AppUniverse<U> {
+    /// Acquire read access to the state as a `ReadCow`, for callers that only sometimes need the
+    /// state to outlive the borrow.
+    ///
+    /// This always returns `ReadCow::Borrowed`, the state is never cloned automatically. Callers
+    /// that need ownership call `into_owned` (or `to_mut`), which clones it then.
+    pub fn read_cow(&self) -> ReadCow<'_, U> {
+        ReadCow::Borrowed(self.read())
+    }
+}
@@ -0,0 +1,52 @@
+use super::{AppUniverse, AppUniverseCore, UniverseError, UniverseSubscription};
+use std::cell::Ref;
+
+/// A `UniverseReader` is a handle to a universe that can read and subscribe, but can't dispatch.
+/// It's obtained from `AppUniverse::reader` and is meant for view code that shouldn't change the
+/// state by accident.
+///
+/// It shares everything with the universe it came from. Cloning it is cheap.
+pub struct UniverseReader<U: AppUniverseCore>(AppUniverse<U>);
+
+impl<U: AppUniverseCore + 'static> UniverseReader<U> {
+    /// Acquire read access to the state.
+    pub fn read(&self) -> Ref<'_, U> {
+        self.0.read()
+    }
+
+    /// Returns the number of messages applied so far, see `AppUniverse::version`.
+    pub fn version(&self) -> u64 {
+        self.0.version()
+    }
+
+    /// This function takes a subscriber function that runs anytime the state changes, like
+    /// `AppUniverse::subscribe`. The subscriber receives a reader as well.
+    pub fn subscribe(
+        &mut self,
+        mut subscriber_fn: impl FnMut(UniverseReader<U>) + 'static,
+    ) -> UniverseSubscription<U> {
+        self.0
+            .subscribe(move |universe| subscriber_fn(UniverseReader(universe)))
+    }
+
+    /// This function removes a subscription, like `AppUniverse::unsubscribe`.
+    pub fn unsubscribe(
+        &mut self,
+        subscription: UniverseSubscription<U>,
+    ) -> Result<(), UniverseError> {
+        self.0.unsubscribe(subscription)
+    }
+}
+
+impl<U: AppUniverseCore> Clone for UniverseReader<U> {
+    fn clone(&self) -> Self {
+        UniverseReader(self.0.clone())
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Returns a read-only handle to this universe.
+    pub fn reader(&self) -> UniverseReader<U> {
+        UniverseReader(self.clone())
+    }
+}
@@ -0,0 +1,29 @@
+use super::{dispatch::QueuedDispatch, AppUniverse, AppUniverseCore, Dispatcher};
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Replaces the whole state with `state` and notifies subscribers once, e.g. to hydrate from a
+    /// saved snapshot or to set up a test.
+    ///
+    /// Replacing counts as one change, so the version goes up by one. Like `with_mut`, it isn't
+    /// recorded as a message. When it's called from a subscriber the state is replaced after the
+    /// current notification pass.
+    pub fn replace_state(&self, state: U) {
+        self.reset_with(move || state);
+    }
+
+    /// Replaces the whole state with the one `init` creates, like `replace_state`.
+    ///
+    /// `init` runs when the replacement is applied, so a reset requested from a subscriber only
+    /// builds the new state after the current notification pass.
+    pub fn reset_with(&self, init: impl FnOnce() -> U + 'static) {
+        let producer = Box::new(move |universe: &mut U, _: &Dispatcher<U>| {
+            *universe = init();
+            1
+        });
+
+        self.enqueue(QueuedDispatch::produced(
+            producer,
+            self.inner.dispatch_queue.current_id(),
+        ));
+    }
+}
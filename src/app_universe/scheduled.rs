@@ -0,0 +1,67 @@
+use super::{AppUniverse, AppUniverseCore, UniverseSubscription};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+/// A `TickScheduler` runs a task on the next tick of an event loop, e.g. the next animation frame
+/// with `requestAnimationFrame` on the web or the next iteration of a native loop fed by a channel.
+/// It decides when `AppUniverse::subscribe_scheduled` flushes.
+///
+/// `schedule` must run every task it receives exactly once, eventually. It may also run it right
+/// away, like `ImmediateScheduler`. It's implemented for any `Fn(Box<dyn FnOnce()>)`.
+pub trait TickScheduler {
+    /// Runs `task` once, on the next tick.
+    fn schedule(&self, task: Box<dyn FnOnce()>);
+}
+
+impl<F: Fn(Box<dyn FnOnce()>)> TickScheduler for F {
+    fn schedule(&self, task: Box<dyn FnOnce()>) {
+        self(task)
+    }
+}
+
+/// An `ImmediateScheduler` runs every task as soon as it's scheduled, e.g. to test scheduled
+/// subscribers without an event loop.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImmediateScheduler;
+
+impl TickScheduler for ImmediateScheduler {
+    fn schedule(&self, task: Box<dyn FnOnce()>) {
+        task()
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function takes a subscriber function that runs at most once per tick of `scheduler`,
+    /// however many times the state changed in between.
+    ///
+    /// The first notification schedules a flush, and the notifications until that flush runs are
+    /// coalesced into it. The subscriber sees the state as of the flush. A flush that was already
+    /// scheduled still runs after unsubscribing.
+    pub fn subscribe_scheduled(
+        &mut self,
+        scheduler: impl TickScheduler + 'static,
+        subscriber_fn: Box<dyn FnMut(AppUniverse<U>)>,
+    ) -> UniverseSubscription<U> {
+        let subscriber_fn = Rc::new(RefCell::new(subscriber_fn));
+        let scheduled = Rc::new(Cell::new(false));
+
+        self.subscribe(move |universe| {
+            if scheduled.replace(true) {
+                return;
+            }
+
+            let subscriber_fn = subscriber_fn.clone();
+            let scheduled = scheduled.clone();
+            let universe = universe.downgrade();
+
+            scheduler.schedule(Box::new(move || {
+                scheduled.set(false);
+                if let Some(universe) = universe.upgrade() {
+                    (subscriber_fn.borrow_mut())(universe);
+                }
+            }));
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use super::{AppUniverse, AppUniverseCore, UniverseSubscription};
+
+/// The epsilon used by `approx_eq`.
+pub const DEFAULT_EPSILON: f64 = 1e-9;
+
+/// `ApproxEq` compares floating-point values, and collections of them, up to an epsilon.
+pub trait ApproxEq {
+    /// Returns `true` if `self` and `other` differ by at most `epsilon`. Collections must have the
+    /// same length and be approximately equal element by element.
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool;
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        (self - other).abs() <= epsilon
+    }
+}
+
+impl ApproxEq for f32 {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        f64::from(*self).approx_eq(&f64::from(*other), epsilon)
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for [T] {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other)
+                .all(|(value, other)| value.approx_eq(other, epsilon))
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Vec<T> {
+    fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.as_slice().approx_eq(other.as_slice(), epsilon)
+    }
+}
+
+/// Returns a comparator for `subscribe_selector` that treats values within `DEFAULT_EPSILON`
+/// (`1e-9`) of each other as equal.
+pub fn approx_eq<T: ApproxEq + ?Sized>() -> impl Fn(&T, &T) -> bool {
+    approx_eq_within(DEFAULT_EPSILON)
+}
+
+/// Returns a comparator for `subscribe_selector` that treats values within `epsilon` of each other
+/// as equal.
+pub fn approx_eq_within<T: ApproxEq + ?Sized>(epsilon: f64) -> impl Fn(&T, &T) -> bool {
+    move |value, other| value.approx_eq(other, epsilon)
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function takes a `select` function that picks a value from the state, and a `callback`
+    /// that only runs when that value changes, like `useSelector` in Redux.
+    ///
+    /// This is `subscribe_selector` with `PartialEq` as the comparison, e.g.
+    /// `universe.subscribe_select(|state| state.cart.len(), |len| ...)`.
+    pub fn subscribe_select<T: PartialEq + 'static>(
+        &mut self,
+        select: impl Fn(&U) -> T + 'static,
+        callback: impl FnMut(&T) + 'static,
+    ) -> UniverseSubscription<U> {
+        self.subscribe_selector(select, T::eq, callback)
+    }
+
+    /// This function takes a `select` function that picks a value from the state, and a `callback`
+    /// that only runs when that value changes according to `eq`.
+    ///
+    /// The value is selected when subscribing and after every notification. `callback` runs when
+    /// `eq` reports that it differs from the value the callback last ran with (or the initial one),
+    /// so many small changes that each pass `eq` still add up to a call eventually. Use `approx_eq`
+    /// as `eq` for floating-point values that jitter.
+    pub fn subscribe_selector<T: 'static>(
+        &mut self,
+        select: impl Fn(&U) -> T + 'static,
+        eq: impl Fn(&T, &T) -> bool + 'static,
+        mut callback: impl FnMut(&T) + 'static,
+    ) -> UniverseSubscription<U> {
+        let mut last = select(&self.read());
+
+        self.subscribe(move |universe| {
+            let value = select(&universe.read());
+            if !eq(&last, &value) {
+                callback(&value);
+                last = value;
+            }
+        })
+    }
+}
@@ -0,0 +1,107 @@
+//! Integration with [futures-signals](https://docs.rs/futures-signals), so slices of the state can
+//! be used with `SignalExt` combinators and dominator's DOM bindings.
+
+use super::{AppUniverse, AppUniverseCore, SubscriptionGuard};
+use futures_signals::{
+    signal::{Mutable, MutableSignalCloned, Signal},
+    signal_vec::{MutableSignalVec, MutableVec, SignalVec, VecDiff},
+};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Returns a `Signal` of the slice of the state picked by `selector`.
+    ///
+    /// The signal yields the current slice first and then every slice that differs from the
+    /// previous one. It's kept up to date by a subscription that is removed once the signal is
+    /// dropped.
+    pub fn signal<T, F>(&self, selector: F) -> impl Signal<Item = T>
+    where
+        T: Clone + PartialEq + 'static,
+        F: Fn(&U) -> T + 'static,
+    {
+        let mutable = Mutable::new(selector(&self.read()));
+
+        let updated = mutable.clone();
+        let subscription = self.clone().subscribe(move |universe| {
+            updated.set_neq(selector(&universe.read()));
+        });
+
+        UniverseSignal {
+            signal: mutable.signal_cloned(),
+            _guard: SubscriptionGuard::new(self, subscription),
+        }
+    }
+
+    /// Returns a `SignalVec` of the `Vec` picked by `selector`.
+    ///
+    /// Pushes, pops and single replaced items are sent as such, any other change replaces the whole
+    /// vector. Like `signal`, the subscription keeping it up to date is removed once it's dropped.
+    pub fn signal_vec<T, F>(&self, selector: F) -> impl SignalVec<Item = T>
+    where
+        T: Clone + PartialEq + 'static,
+        F: Fn(&U) -> Vec<T> + 'static,
+    {
+        let mutable = MutableVec::new_with_values(selector(&self.read()));
+
+        let updated = mutable.clone();
+        let subscription = self.clone().subscribe(move |universe| {
+            apply_diff(&updated, selector(&universe.read()));
+        });
+
+        UniverseSignalVec {
+            signal: mutable.signal_vec_cloned(),
+            _guard: SubscriptionGuard::new(self, subscription),
+        }
+    }
+}
+
+// Updates `mutable` to `values` with the smallest change it can find.
+fn apply_diff<T: Clone + PartialEq>(mutable: &MutableVec<T>, mut values: Vec<T>) {
+    let mut current = mutable.lock_mut();
+    let (old_len, new_len) = (current.len(), values.len());
+
+    if new_len == old_len + 1 && current[..] == values[..old_len] {
+        current.push_cloned(values.pop().unwrap());
+    } else if new_len + 1 == old_len && current[..new_len] == values[..] {
+        current.pop();
+    } else if new_len == old_len {
+        let mut changed = (0..new_len).filter(|&index| current[index] != values[index]);
+
+        match (changed.next(), changed.next()) {
+            (None, _) => {}
+            (Some(index), None) => current.set_cloned(index, values.swap_remove(index)),
+            (Some(_), Some(_)) => current.replace_cloned(values),
+        }
+    } else {
+        current.replace_cloned(values);
+    }
+}
+
+struct UniverseSignal<T, U: AppUniverseCore + 'static> {
+    signal: MutableSignalCloned<T>,
+    _guard: SubscriptionGuard<U>,
+}
+
+impl<T: Clone, U: AppUniverseCore + 'static> Signal for UniverseSignal<T, U> {
+    type Item = T;
+
+    fn poll_change(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut self.signal).poll_change(cx)
+    }
+}
+
+struct UniverseSignalVec<T, U: AppUniverseCore + 'static> {
+    signal: MutableSignalVec<T>,
+    _guard: SubscriptionGuard<U>,
+}
+
+impl<T: Clone, U: AppUniverseCore + 'static> SignalVec for UniverseSignalVec<T, U> {
+    type Item = T;
+
+    fn poll_vec_change(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<VecDiff<T>>> {
+        Pin::new(&mut self.signal).poll_vec_change(cx)
+    }
+}
@@ -0,0 +1,64 @@
+use super::{dispatch::QueuedDispatch, AppUniverse, AppUniverseCore, Dispatcher};
+use std::rc::Rc;
+
+/// A `Snapshot` is a checkpoint of the state taken with `AppUniverse::snapshot`, which can be
+/// restored with `AppUniverse::restore_snapshot` at any time.
+///
+/// It holds its own copy of the state, so later dispatches don't affect it. Cloning it is cheap
+/// and all clones share that copy.
+pub struct Snapshot<U> {
+    state: Rc<U>,
+    version: u64,
+}
+
+impl<U> Snapshot<U> {
+    /// Returns the state this snapshot was taken of.
+    pub fn state(&self) -> &U {
+        &self.state
+    }
+
+    /// Returns the version of the state when the snapshot was taken.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+impl<U> Clone for Snapshot<U> {
+    fn clone(&self) -> Self {
+        Snapshot {
+            state: self.state.clone(),
+            version: self.version,
+        }
+    }
+}
+
+impl<U: AppUniverseCore + Clone + 'static> AppUniverse<U> {
+    /// Takes a snapshot of the current state, e.g. "before import", that can be restored later.
+    ///
+    /// Unlike an undo history, callers keep as many snapshots as they like and restore them in any
+    /// order.
+    pub fn snapshot(&self) -> Snapshot<U> {
+        Snapshot {
+            state: Rc::new(self.read().clone()),
+            version: self.inner.version.get(),
+        }
+    }
+
+    /// Replaces the state with a clone of the state of `snapshot` and notifies subscribers.
+    ///
+    /// Restoring counts as one change, so the version goes up by one instead of going back to the
+    /// version of the snapshot. Like `with_mut`, it isn't recorded as a message. When it's called
+    /// from a subscriber the state is restored after the current notification pass.
+    pub fn restore_snapshot(&self, snapshot: &Snapshot<U>) {
+        let state = snapshot.state.clone();
+        let producer = Box::new(move |universe: &mut U, _: &Dispatcher<U>| {
+            *universe = (*state).clone();
+            1
+        });
+
+        self.enqueue(QueuedDispatch::produced(
+            producer,
+            self.inner.dispatch_queue.current_id(),
+        ));
+    }
+}
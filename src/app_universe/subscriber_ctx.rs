@@ -0,0 +1,61 @@
+use super::{
+    AppUniverse, AppUniverseCore, BatchInfo, CtxSubscriberFn, Subscription, SubscriptionId,
+    UniverseSubscription, WeakAppUniverse,
+};
+use std::cell::{Cell, Ref};
+
+/// The `SubscriberCtx` is passed to subscribers registered with `subscribe_with_ctx`, and describes
+/// the subscription that is being notified.
+pub struct SubscriberCtx<'a, U: AppUniverseCore> {
+    pub(crate) universe: &'a AppUniverse<U>,
+    pub(crate) id: SubscriptionId,
+    pub(crate) unsubscribed: &'a Cell<bool>,
+    pub(crate) batch: BatchInfo,
+}
+
+impl<U: AppUniverseCore + 'static> SubscriberCtx<'_, U> {
+    /// Removes this subscription once the current notification pass is over.
+    ///
+    /// The subscriber keeps running until it returns, so it's fine to do more work afterwards.
+    pub fn unsubscribe_self(&self) {
+        self.unsubscribed.set(true);
+    }
+
+    /// Dispatches `msg`. It's queued and applied after the current notification pass.
+    pub fn dispatch(&self, msg: U::Message) {
+        self.universe.msg(msg);
+    }
+
+    /// Returns the id of this subscription.
+    pub fn subscription_id(&self) -> SubscriptionId {
+        self.id
+    }
+
+    /// Returns what changed since the previous notification.
+    pub fn batch(&self) -> BatchInfo {
+        self.batch
+    }
+
+    /// Acquire read access to the state.
+    pub fn read(&self) -> Ref<'_, U> {
+        self.universe.read()
+    }
+
+    /// Returns a weak handle to the universe, which can be kept around without creating a cycle.
+    pub fn universe(&self) -> WeakAppUniverse<U> {
+        self.universe.downgrade()
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function takes a subscriber function that runs anytime the state changes and receives a
+    /// `SubscriberCtx`, which lets it dispatch and unsubscribe itself without holding on to the
+    /// universe or its subscription.
+    pub fn subscribe_with_ctx(
+        &mut self,
+        subscriber_fn: CtxSubscriberFn<U>,
+    ) -> UniverseSubscription<U> {
+        let id = self.next_subscription_id();
+        self.add_subscription(Subscription::with_ctx(id, subscriber_fn))
+    }
+}
@@ -0,0 +1,40 @@
+use super::{AppUniverse, AppUniverseCore, Subscription, SubscriptionId, UniverseSubscription};
+use std::error::Error;
+
+pub(crate) type ErrorHandler = Box<dyn FnMut(SubscriptionId, Box<dyn Error>)>;
+
+// The handler every universe starts with.
+pub(crate) fn log_subscriber_error(id: SubscriptionId, error: Box<dyn Error>) {
+    log::error!("Subscriber {:?} failed: {}", id, error);
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function takes a fallible subscriber function that runs anytime the state changes.
+    ///
+    /// Errors returned by `subscriber_fn` are passed to the handler set with
+    /// `set_subscriber_error_handler`. The subscriber stays registered after an error and the
+    /// remaining subscribers still run.
+    pub fn subscribe_fallible<E: Error + 'static>(
+        &mut self,
+        mut subscriber_fn: impl FnMut(AppUniverse<U>) -> Result<(), E> + 'static,
+    ) -> UniverseSubscription<U> {
+        let id = self.next_subscription_id();
+
+        let callback = Box::new(move |universe: AppUniverse<U>| {
+            if let Err(error) = subscriber_fn(universe.clone()) {
+                (universe.inner.subscriber_error_handler.borrow_mut())(id, Box::new(error));
+            }
+        });
+
+        self.add_subscription(Subscription::new(id, callback))
+    }
+
+    /// Sets the handler that receives errors returned by fallible subscribers, along with the id of
+    /// the subscription that returned them. By default errors are logged with `log::error!`.
+    pub fn set_subscriber_error_handler(
+        &self,
+        handler: impl FnMut(SubscriptionId, Box<dyn Error>) + 'static,
+    ) {
+        *self.inner.subscriber_error_handler.borrow_mut() = Box::new(handler);
+    }
+}
@@ -0,0 +1,58 @@
+use super::{AppUniverse, AppUniverseCore, UniverseSubscription, WeakAppUniverse};
+
+/// A `SubscriptionGroup` collects subscriptions so they can be removed together, e.g. when a page
+/// is torn down. It's created with `AppUniverse::subscription_group`.
+///
+/// The group doesn't keep the universe alive, and dropping it doesn't unsubscribe anything.
+pub struct SubscriptionGroup<U: AppUniverseCore> {
+    universe: WeakAppUniverse<U>,
+    subscriptions: Vec<UniverseSubscription<U>>,
+}
+
+impl<U: AppUniverseCore + 'static> SubscriptionGroup<U> {
+    /// Adds `subscription` to the group.
+    pub fn add(&mut self, subscription: UniverseSubscription<U>) {
+        self.subscriptions.push(subscription);
+    }
+
+    /// Returns the number of subscriptions in the group.
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Returns `true` if the group has no subscriptions.
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    /// Removes every subscription of the group from the universe and empties the group. Returns how
+    /// many were still registered.
+    pub fn unsubscribe_all(&mut self) -> usize {
+        let subscriptions = std::mem::take(&mut self.subscriptions);
+        match self.universe.upgrade() {
+            Some(mut universe) => subscriptions
+                .into_iter()
+                .filter(|subscription| universe.try_unsubscribe(subscription.clone()))
+                .count(),
+            None => 0,
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Creates an empty `SubscriptionGroup` for subscriptions to this universe.
+    pub fn subscription_group(&self) -> SubscriptionGroup<U> {
+        SubscriptionGroup {
+            universe: self.downgrade(),
+            subscriptions: vec![],
+        }
+    }
+
+    /// This function removes every subscription, e.g. when the whole app is torn down.
+    ///
+    /// Called from a subscriber, the subscribers that haven't run yet are skipped for the rest of
+    /// the notification pass.
+    pub fn clear_subscriptions(&mut self) {
+        self.remove_subscriptions(|_| true);
+    }
+}
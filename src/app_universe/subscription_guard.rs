@@ -0,0 +1,53 @@
+use super::{AppUniverse, AppUniverseCore, UniverseSubscription, WeakAppUniverse};
+
+/// A `SubscriptionGuard` removes its subscription from the universe when it's dropped, e.g. when
+/// the component that subscribed is torn down. It's returned by `AppUniverse::subscribe_guarded`.
+///
+/// The guard doesn't keep the universe alive.
+#[must_use = "dropping the guard unsubscribes right away"]
+pub struct SubscriptionGuard<U: AppUniverseCore + 'static> {
+    universe: WeakAppUniverse<U>,
+    subscription: Option<UniverseSubscription<U>>,
+}
+
+impl<U: AppUniverseCore + 'static> SubscriptionGuard<U> {
+    pub(crate) fn new(universe: &AppUniverse<U>, subscription: UniverseSubscription<U>) -> Self {
+        SubscriptionGuard {
+            universe: universe.downgrade(),
+            subscription: Some(subscription),
+        }
+    }
+
+    /// Returns the subscription this guard removes.
+    pub fn subscription(&self) -> &UniverseSubscription<U> {
+        self.subscription.as_ref().expect("only taken on drop")
+    }
+
+    /// Gives up the guard without unsubscribing, returning the subscription to be removed with
+    /// `unsubscribe` instead.
+    pub fn into_subscription(mut self) -> UniverseSubscription<U> {
+        self.subscription.take().expect("only taken on drop")
+    }
+}
+
+impl<U: AppUniverseCore + 'static> Drop for SubscriptionGuard<U> {
+    fn drop(&mut self) {
+        if let (Some(mut universe), Some(subscription)) =
+            (self.universe.upgrade(), self.subscription.take())
+        {
+            universe.try_unsubscribe(subscription);
+        }
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function takes a subscriber function like `subscribe`, and returns a guard that
+    /// unsubscribes it when dropped instead of a handle that has to be passed to `unsubscribe`.
+    pub fn subscribe_guarded(
+        &mut self,
+        subscriber_fn: impl FnMut(AppUniverse<U>) + 'static,
+    ) -> SubscriptionGuard<U> {
+        let subscription = self.subscribe(subscriber_fn);
+        SubscriptionGuard::new(self, subscription)
+    }
+}
@@ -0,0 +1,52 @@
+use super::{AppUniverse, AppUniverseCore, BatchInfo, SubscriberPriority, SubscriptionId};
+
+/// A `SubscriptionMeta` describes a registered subscription to the predicate of `notify_selective`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SubscriptionMeta<'a> {
+    /// The id of the subscription.
+    pub id: SubscriptionId,
+    /// The label of the subscription, if it was given one.
+    pub label: Option<&'a str>,
+    /// The key of the subscription, if it was registered with `subscribe_unique`.
+    pub key: Option<&'a str>,
+    /// The priority the subscription is notified with.
+    pub priority: SubscriberPriority,
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Runs only the subscribers whose metadata matches `pred`, right away and in their usual
+    /// order, without a state change.
+    ///
+    /// This is an advanced, manual notification for when something outside of the universe
+    /// affects some subscribers, e.g. a theme change that only the labeled rendering subscribers
+    /// care about. It doesn't touch the version, doesn't count as a notification for `flush`,
+    /// `wait_for_change` or `BatchInfo`, and subscribers see an empty batch.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called from a subscriber (or anything else that runs while a dispatch is being
+    /// applied), since the subscribers are being notified then.
+    pub fn notify_selective(&self, pred: impl Fn(&SubscriptionMeta<'_>) -> bool) {
+        assert!(
+            !self.inner.dispatch_queue.is_draining(),
+            "notify_selective can't be called while a dispatch is being applied"
+        );
+
+        let version = self.inner.version.get();
+        let batch = BatchInfo {
+            message_count: 0,
+            start_version: version,
+            end_version: version,
+        };
+
+        self.run_subscribers(batch, |subscription| {
+            pred(&SubscriptionMeta {
+                id: subscription.id,
+                label: subscription.label.as_deref(),
+                key: subscription.key.as_deref(),
+                priority: subscription.priority.get(),
+            })
+        });
+    }
+}
@@ -0,0 +1,46 @@
+//! Integration with [Sycamore](https://sycamore-rs.netlify.app), where the universe is exposed to
+//! components as a signal.
+//!
+//! ```rust,ignore
+//! use sycamore::prelude::*;
+//!
+//! #[component]
+//! fn Counter<G: Html>(cx: Scope, universe: AppUniverse<CounterState>) -> View<G> {
+//!     let state = universe.to_sycamore_signal(cx);
+//!
+//!     view! { cx,
+//!         button(on:click=move |_| universe.msg(Msg::Increment(1))) {
+//!             (state.get().counter)
+//!         }
+//!     }
+//! }
+//! ```
+
+use super::{AppUniverse, AppUniverseCore};
+use std::ops::Deref;
+use sycamore::reactive::{create_rc_signal, create_ref, on_cleanup, ReadSignal, Scope};
+
+impl<U: AppUniverseCore + Clone + 'static> AppUniverse<U> {
+    /// Returns a signal that holds the state and is updated every time subscribers are notified.
+    ///
+    /// `get` on the signal returns an `Rc<U>`, so reading it doesn't clone the state, but every
+    /// notification clones it once into the signal. The subscription is removed when `cx` is
+    /// disposed.
+    pub fn to_sycamore_signal<'a>(&self, cx: Scope<'a>) -> &'a ReadSignal<U> {
+        let signal = create_rc_signal(self.read().clone());
+
+        let mut universe = self.clone();
+        let subscription = {
+            let signal = signal.clone();
+            universe.subscribe(move |universe| {
+                let state = universe.read().clone();
+                signal.set(state);
+            })
+        };
+        on_cleanup(cx, move || {
+            universe.try_unsubscribe(subscription);
+        });
+
+        create_ref(cx, signal).deref()
+    }
+}
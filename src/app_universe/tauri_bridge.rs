@@ -0,0 +1,173 @@
+//! Integration with [Tauri](https://tauri.app), where the universe lives in the Rust backend and the
+//! webview displays it and dispatches messages to it.
+//!
+//! The universe stays on the main thread. Messages invoked from the webview may arrive on any thread,
+//! so they are forwarded to the main thread before they are dispatched.
+//!
+//! ```rust,ignore
+//! let universe = AppUniverse::new(Counter::default());
+//!
+//! tauri::Builder::default()
+//!     .plugin(TauriUniversePlugin::new(universe.clone()))
+//!     .run(tauri::generate_context!())
+//!     .unwrap();
+//! ```
+//!
+//! The frontend dispatches with `invoke("plugin:universe|dispatch", { message })` and listens to
+//! `universe://changed`, whose payload is the serialized state. Like every plugin command,
+//! `dispatch` has to be allowed by a capability, e.g. by declaring it with
+//! `tauri_build::InlinedPlugin::new().commands(&["dispatch"])` and granting
+//! `universe:allow-dispatch`.
+
+use super::{AppUniverse, AppUniverseCore};
+use send_wrapper::SendWrapper;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tauri::{ipc::InvokeBody, plugin::Plugin, AppHandle, Emitter, Runtime};
+
+/// The event emitted to every window with the serialized state whenever state changes.
+pub const UNIVERSE_CHANGED_EVENT: &str = "universe://changed";
+
+// The universe may only be touched on the main thread. Only the plugin holds a strong reference,
+// everything that crosses threads holds a weak one so the universe is never dropped elsewhere.
+type MainThreadUniverse<U> = Arc<SendWrapper<AppUniverse<U>>>;
+
+#[derive(Deserialize)]
+struct DispatchArgs<M> {
+    message: M,
+}
+
+/// The `TauriUniversePlugin` exposes a universe to the webviews of a Tauri app.
+///
+/// It registers the `dispatch` command, which deserializes a message from the `message` argument
+/// and dispatches it, and emits `UNIVERSE_CHANGED_EVENT` on every notification.
+pub struct TauriUniversePlugin<U: AppUniverseCore> {
+    universe: MainThreadUniverse<U>,
+    debounce: Option<Duration>,
+}
+
+impl<U> TauriUniversePlugin<U>
+where
+    U: AppUniverseCore + Serialize + 'static,
+    U::Message: DeserializeOwned + Send + 'static,
+{
+    /// Creates a plugin for `universe`.
+    ///
+    /// This has to be called from the main thread, which is where the universe is used from then on.
+    pub fn new(universe: AppUniverse<U>) -> Self {
+        TauriUniversePlugin {
+            universe: Arc::new(SendWrapper::new(universe)),
+            debounce: None,
+        }
+    }
+
+    /// Emits the changed event at most once per `delay`, with the state as it is when the delay
+    /// has passed, instead of on every notification.
+    pub fn debounce(mut self, delay: Duration) -> Self {
+        self.debounce = Some(delay);
+        self
+    }
+}
+
+impl<R, U> Plugin<R> for TauriUniversePlugin<U>
+where
+    R: Runtime,
+    U: AppUniverseCore + Serialize + 'static,
+    U::Message: DeserializeOwned + Send + 'static,
+{
+    fn name(&self) -> &'static str {
+        "universe"
+    }
+
+    fn initialize(
+        &mut self,
+        app: &AppHandle<R>,
+        _config: serde_json::Value,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let app = app.clone();
+        let mut universe = AppUniverse::clone(&self.universe);
+
+        match self.debounce {
+            None => {
+                universe.subscribe_forever(move |universe| emit_state(&app, &universe));
+            }
+            Some(delay) => {
+                let weak_universe = Arc::downgrade(&self.universe);
+                let scheduled = Arc::new(AtomicBool::new(false));
+
+                universe.subscribe_forever(move |_| {
+                    if scheduled.swap(true, Ordering::SeqCst) {
+                        return;
+                    }
+
+                    let app = app.clone();
+                    let weak_universe = weak_universe.clone();
+                    let scheduled = scheduled.clone();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(delay);
+
+                        let emitter = app.clone();
+                        let _ = app.run_on_main_thread(move || {
+                            scheduled.store(false, Ordering::SeqCst);
+                            if let Some(universe) = weak_universe.upgrade() {
+                                emit_state(&emitter, &universe);
+                            }
+                        });
+                    });
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn extend_api(&mut self, invoke: tauri::ipc::Invoke<R>) -> bool {
+        if invoke.message.command() != "dispatch" {
+            return false;
+        }
+
+        let args = match invoke.message.payload() {
+            InvokeBody::Json(payload) => {
+                serde_json::from_value::<DispatchArgs<U::Message>>(payload.clone())
+            }
+            InvokeBody::Raw(payload) => serde_json::from_slice(payload),
+        };
+
+        let message = match args {
+            Ok(args) => args.message,
+            Err(error) => {
+                invoke.resolver.reject(error.to_string());
+                return true;
+            }
+        };
+
+        let universe = Arc::downgrade(&self.universe);
+        let dispatched = invoke.message.webview_ref().run_on_main_thread(move || {
+            if let Some(universe) = universe.upgrade() {
+                universe.msg(message);
+            }
+        });
+
+        match dispatched {
+            Ok(()) => invoke.resolver.resolve(()),
+            Err(error) => invoke.resolver.reject(error.to_string()),
+        }
+
+        true
+    }
+}
+
+fn emit_state<R: Runtime, U: AppUniverseCore + Serialize + 'static>(
+    app: &AppHandle<R>,
+    universe: &AppUniverse<U>,
+) {
+    if let Err(error) = app.emit(UNIVERSE_CHANGED_EVENT, &*universe.read()) {
+        log::error!("Failed to emit {}: {}", UNIVERSE_CHANGED_EVENT, error);
+    }
+}
@@ -0,0 +1,248 @@
+#[cfg(feature = "async")]
+use super::UniverseError;
+use super::{AppUniverseCore, SubscriptionId};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard,
+    },
+};
+#[cfg(feature = "async")]
+use std::{future::Future, pin::Pin};
+
+type ThreadedSubscriberFn<U> = Arc<Mutex<Box<dyn FnMut(AppUniverseThreaded<U>) + Send>>>;
+type ThreadedSubscriptions<U> = Arc<Mutex<Vec<(SubscriptionId, ThreadedSubscriberFn<U>)>>>;
+
+// The messages waiting to be applied, and whether a thread is already applying them.
+struct ThreadedQueue<M> {
+    messages: VecDeque<M>,
+    draining: bool,
+}
+
+// Resets the queue if a reducer or subscriber panics halfway through a drain, so the universe
+// stays usable for callers that catch the panic.
+struct ThreadedDrainGuard<'a, M>(&'a Mutex<ThreadedQueue<M>>);
+
+impl<M> Drop for ThreadedDrainGuard<'_, M> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            let mut queue = lock(self.0);
+            queue.draining = false;
+            queue.messages.clear();
+        }
+    }
+}
+
+// A panic while a lock is held only poisons it, the data behind it is still consistent because
+// messages are applied one at a time.
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The `ThreadedSubscription` is returned by `AppUniverseThreaded::subscribe`. Its only purpose is
+/// to be passed into `AppUniverseThreaded::unsubscribe`.
+#[must_use = "ignoring the subscription handle means you can never unsubscribe"]
+#[derive(Debug, Clone)]
+pub struct ThreadedSubscription(SubscriptionId);
+
+impl ThreadedSubscription {
+    /// Returns the id of this subscription.
+    pub fn id(&self) -> SubscriptionId {
+        self.0
+    }
+}
+
+/// An `AppUniverseThreaded` is a universe that can be shared between threads, e.g. when network
+/// callbacks of a Tauri backend or a desktop app land on worker threads.
+///
+/// It's the `Arc<RwLock<..>>` counterpart of `AppUniverse` for the same `AppUniverseCore`, with a
+/// smaller API: messages are applied with `msg` (follow-ups through `msg_with` aren't available)
+/// and subscribers have to be `Send`.
+///
+/// Cloning it is cheap and all clones refer to the same universe.
+///
+/// To be shared between threads, `U` has to be `Send + Sync` and `U::Message` has to be `Send`.
+/// Messages are queued and may be applied by another thread than the one that sent them, and the
+/// ones produced by `dispatch_async` always cross threads, which is why it requires these bounds.
+pub struct AppUniverseThreaded<U: AppUniverseCore> {
+    universe: Arc<RwLock<U>>,
+    subscriptions: ThreadedSubscriptions<U>,
+    queue: Arc<Mutex<ThreadedQueue<U::Message>>>,
+    version: Arc<AtomicU64>,
+    next_subscription_id: Arc<AtomicU64>,
+    #[cfg(feature = "async")]
+    spawner: Arc<Mutex<Option<Arc<dyn SendSpawner>>>>,
+}
+
+impl<U: AppUniverseCore> Clone for AppUniverseThreaded<U> {
+    fn clone(&self) -> Self {
+        AppUniverseThreaded {
+            universe: self.universe.clone(),
+            subscriptions: self.subscriptions.clone(),
+            queue: self.queue.clone(),
+            version: self.version.clone(),
+            next_subscription_id: self.next_subscription_id.clone(),
+            #[cfg(feature = "async")]
+            spawner: self.spawner.clone(),
+        }
+    }
+}
+
+impl<U: AppUniverseCore> AppUniverseThreaded<U> {
+    /// This creates a new threaded universe.
+    pub fn new(universe_core: U) -> Self {
+        AppUniverseThreaded {
+            universe: Arc::new(RwLock::new(universe_core)),
+            subscriptions: Arc::new(Mutex::new(vec![])),
+            queue: Arc::new(Mutex::new(ThreadedQueue {
+                messages: VecDeque::new(),
+                draining: false,
+            })),
+            version: Arc::new(AtomicU64::new(0)),
+            next_subscription_id: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "async")]
+            spawner: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// This method allows for mutation of state by sending a message, from any thread.
+    ///
+    /// Messages are applied one at a time, each followed by a notification of the subscribers. A
+    /// message sent while another thread (or a subscriber) is applying messages is queued and
+    /// applied by that thread, so `msg` may return before it was applied.
+    ///
+    /// If a reducer or subscriber panics, the messages still waiting are dropped and the universe
+    /// keeps working for the next `msg`.
+    pub fn msg(&self, msg: U::Message) {
+        let mut queue = lock(&self.queue);
+        queue.messages.push_back(msg);
+        if queue.draining {
+            return;
+        }
+        queue.draining = true;
+        drop(queue);
+
+        let _guard = ThreadedDrainGuard(&self.queue);
+        loop {
+            let msg = {
+                let mut queue = lock(&self.queue);
+                match queue.messages.pop_front() {
+                    Some(msg) => msg,
+                    None => {
+                        queue.draining = false;
+                        return;
+                    }
+                }
+            };
+
+            self.universe
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .msg(msg);
+            self.version.fetch_add(1, Ordering::SeqCst);
+            self.notify_subscribers();
+        }
+    }
+
+    // Subscribers are collected first, so they can subscribe and unsubscribe while being notified.
+    fn notify_subscribers(&self) {
+        let subscribers: Vec<_> = lock(&self.subscriptions)
+            .iter()
+            .map(|(_, subscriber_fn)| subscriber_fn.clone())
+            .collect();
+
+        for subscriber_fn in subscribers {
+            (lock(&subscriber_fn))(self.clone());
+        }
+    }
+
+    /// Acquire read access to the state.
+    ///
+    /// Messages can't be applied while the guard is alive, so it shouldn't be held across a call
+    /// to `msg` on the same thread.
+    pub fn read(&self) -> RwLockReadGuard<'_, U> {
+        self.universe.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the number of messages applied so far.
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// This function takes a subscriber function that runs anytime the state changes, on the
+    /// thread that applied the change.
+    pub fn subscribe(
+        &self,
+        subscriber_fn: Box<dyn FnMut(AppUniverseThreaded<U>) + Send>,
+    ) -> ThreadedSubscription {
+        let id = SubscriptionId(self.next_subscription_id.fetch_add(1, Ordering::SeqCst) + 1);
+        lock(&self.subscriptions).push((id, Arc::new(Mutex::new(subscriber_fn))));
+        ThreadedSubscription(id)
+    }
+
+    /// This function removes `subscription` and returns whether it was still registered.
+    pub fn unsubscribe(&self, subscription: ThreadedSubscription) -> bool {
+        let mut subscriptions = lock(&self.subscriptions);
+        let len_before = subscriptions.len();
+
+        subscriptions.retain(|(id, _)| *id != subscription.0);
+
+        len_before != subscriptions.len()
+    }
+}
+
+/// A boxed future as it's handed to a `SendSpawner`.
+#[cfg(feature = "async")]
+pub type SendBoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A `SendSpawner` runs the futures created by `AppUniverseThreaded::dispatch_async` on an executor
+/// that may move them between threads.
+///
+/// It's implemented for any `Fn(SendBoxFuture) + Send + Sync`, so a closure that forwards to the
+/// executor of your choice (e.g. `tokio::spawn`) is enough.
+#[cfg(feature = "async")]
+pub trait SendSpawner: Send + Sync {
+    /// Runs `future` to completion in the background.
+    fn spawn(&self, future: SendBoxFuture);
+}
+
+#[cfg(feature = "async")]
+impl<F: Fn(SendBoxFuture) + Send + Sync> SendSpawner for F {
+    fn spawn(&self, future: SendBoxFuture) {
+        self(future)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<U> AppUniverseThreaded<U>
+where
+    U: AppUniverseCore + Send + Sync + 'static,
+    U::Message: Send + 'static,
+{
+    /// Sets the spawner used by `dispatch_async`.
+    pub fn set_spawner(&self, spawner: impl SendSpawner + 'static) {
+        *lock(&self.spawner) = Some(Arc::new(spawner));
+    }
+
+    /// Spawns `future` and dispatches the message it resolves to, from whichever thread the
+    /// spawner runs it on.
+    ///
+    /// Unlike `AppUniverse::dispatch_async` the future has to be `Send`, and so does the message.
+    /// Returns an error if no spawner has been set with `set_spawner`.
+    pub fn dispatch_async(
+        &self,
+        future: impl Future<Output = U::Message> + Send + 'static,
+    ) -> Result<(), UniverseError> {
+        let spawner = lock(&self.spawner)
+            .clone()
+            .ok_or(UniverseError::NoSpawner)?;
+
+        let universe = self.clone();
+        spawner.spawn(Box::pin(async move {
+            universe.msg(future.await);
+        }));
+
+        Ok(())
+    }
+}
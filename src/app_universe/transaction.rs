@@ -0,0 +1,57 @@
+use super::{AppUniverse, AppUniverseCore};
+
+impl<U: AppUniverseCore + Clone + 'static> AppUniverse<U> {
+    /// Runs `f`, which dispatches several messages, as one all-or-nothing operation.
+    ///
+    /// Notifications are held back while `f` runs. If it returns `Ok`, subscribers are notified
+    /// once for everything it dispatched, following the notification strategy. If it returns `Err`,
+    /// the state is restored to how it was before and nobody is notified. The version isn't
+    /// restored, it keeps counting the messages that were rolled back, so a version never refers
+    /// to two different states.
+    ///
+    /// Only the state is rolled back. Observers have already seen the messages, and
+    /// `dispatch_count` still counts their dispatches.
+    ///
+    /// The state is cloned up front to be able to restore it. `f` is synchronous, so async work
+    /// started inside it (e.g. `dispatch_async`) isn't part of the transaction.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called while a dispatch is being applied, e.g. from a subscriber, because the
+    /// messages dispatched there are only applied after the current dispatch and couldn't be
+    /// rolled back.
+    pub fn transaction<E>(&self, f: impl FnOnce(&Self) -> Result<(), E>) -> Result<(), E> {
+        assert!(
+            !self.inner.dispatch_queue.is_draining(),
+            "transaction can't be called while a dispatch is being applied"
+        );
+
+        let snapshot = self.inner.universe.borrow().clone();
+        let version = self.inner.version.get();
+
+        self.hold_notifications();
+        let result = f(self);
+        let changed = self.inner.notifier.release();
+
+        match result {
+            Ok(()) => {
+                if changed && self.state_changed() {
+                    self.schedule_notification();
+                }
+            }
+            Err(_) => {
+                let mut universe = self.inner.universe.borrow_mut();
+                *universe = snapshot;
+
+                if let Some(log) = self.inner.message_log.borrow_mut().as_mut() {
+                    log.truncate_after(version);
+                }
+
+                #[cfg(feature = "debug-invariant")]
+                self.record_invariant(&universe);
+            }
+        }
+
+        result
+    }
+}
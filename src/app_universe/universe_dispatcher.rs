@@ -0,0 +1,40 @@
+use super::{AppUniverse, AppUniverseCore, UniverseError};
+
+/// A `UniverseDispatcher` is a handle to a universe that can send messages, but can't read or
+/// subscribe. It's obtained from `AppUniverse::dispatcher` and is meant for event handlers, so
+/// the code that changes the state is easy to tell apart from the code that reads it.
+///
+/// It shares everything with the universe it came from. Cloning it is cheap.
+pub struct UniverseDispatcher<U: AppUniverseCore>(AppUniverse<U>);
+
+impl<U: AppUniverseCore + 'static> UniverseDispatcher<U> {
+    /// This method sends a message, like `AppUniverse::msg`.
+    pub fn msg(&self, msg: U::Message) {
+        self.0.msg(msg);
+    }
+
+    /// This method sends several messages with a single notification, like
+    /// `AppUniverse::msg_batch`.
+    pub fn msg_batch(&self, messages: impl IntoIterator<Item = U::Message>) {
+        self.0.msg_batch(messages);
+    }
+
+    /// This method sends a message, or returns an error if it can't be applied, like
+    /// `AppUniverse::try_msg`.
+    pub fn try_msg(&self, msg: U::Message) -> Result<(), UniverseError> {
+        self.0.try_msg(msg)
+    }
+}
+
+impl<U: AppUniverseCore> Clone for UniverseDispatcher<U> {
+    fn clone(&self) -> Self {
+        UniverseDispatcher(self.0.clone())
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Returns a dispatch-only handle to this universe.
+    pub fn dispatcher(&self) -> UniverseDispatcher<U> {
+        UniverseDispatcher(self.clone())
+    }
+}
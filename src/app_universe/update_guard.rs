@@ -0,0 +1,68 @@
+use super::{AppUniverse, AppUniverseCore};
+
+/// An `UpdateGuard` holds back notifications while it's alive, and notifies subscribers once for
+/// everything dispatched in the meantime when it's dropped. It's returned by
+/// `AppUniverse::begin_update`.
+#[must_use = "dropping the guard ends the update right away"]
+pub struct UpdateGuard<U: AppUniverseCore + 'static> {
+    universe: AppUniverse<U>,
+}
+
+impl<U: AppUniverseCore + 'static> Drop for UpdateGuard<U> {
+    fn drop(&mut self) {
+        self.universe.release_notifications();
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Starts an update that coalesces the notifications of every message dispatched until the
+    /// returned guard is dropped, even across function boundaries.
+    ///
+    /// Messages are applied right away, so `read` sees them, but subscribers are only notified once
+    /// the guard is dropped, and only if anything was dispatched. That notification follows the
+    /// notification strategy. Unlike `transaction` nothing is rolled back.
+    ///
+    /// Guards can be nested and mixed with `pause_notifications`, and notifications resume once the
+    /// last of them ends, whatever the order. Changing the strategy during the update takes effect
+    /// right away and also applies to the notification when the guard is dropped.
+    pub fn begin_update(&self) -> UpdateGuard<U> {
+        self.hold_notifications();
+        UpdateGuard {
+            universe: self.clone(),
+        }
+    }
+
+    /// Stops notifying subscribers until `resume_notifications` is called, e.g. during a bulk
+    /// import or an animation-critical section.
+    ///
+    /// Messages are still applied, so `read` sees them. Calling this while notifications are
+    /// already paused does nothing, pauses don't nest. Changing the notification strategy while
+    /// paused takes effect right away and stays in effect after resuming.
+    pub fn pause_notifications(&self) {
+        if self.inner.notifier.pause() {
+            self.hold_notifications();
+        }
+    }
+
+    /// Notifies subscribers again after `pause_notifications`, starting with a single catch-up
+    /// notification if anything was dispatched while they were paused. It follows the
+    /// notification strategy.
+    pub fn resume_notifications(&self) {
+        if self.inner.notifier.resume() {
+            self.release_notifications();
+        }
+    }
+
+    // Holds back notifications until the matching `release_notifications`.
+    pub(crate) fn hold_notifications(&self) {
+        self.inner.notifier.hold();
+    }
+
+    // Ends a hold, and notifies once if it was the last one and anything was dispatched in the
+    // meantime.
+    pub(crate) fn release_notifications(&self) {
+        if self.inner.notifier.release() && self.state_changed() {
+            self.schedule_notification();
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::{AppUniverse, AppUniverseCore};
+use std::{cell::Ref, ops::Deref};
+
+/// A `VersionedRef` is read access to the state together with the version it has, returned by
+/// `AppUniverse::read_versioned`.
+///
+/// It's meant for consumers that poll every frame: keep the version of the last frame and check
+/// `has_changed` before redoing any work.
+pub struct VersionedRef<'a, U> {
+    state: Ref<'a, U>,
+    version: u64,
+}
+
+impl<U> VersionedRef<'_, U> {
+    /// Returns the version of the state, see `AppUniverse::version`.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns `true` if the state has another version than `last_seen_version`.
+    pub fn has_changed(&self, last_seen_version: &u64) -> bool {
+        self.version != *last_seen_version
+    }
+}
+
+impl<U> Deref for VersionedRef<'_, U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        &self.state
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// Acquire read access to the state like `read`, together with its version.
+    pub fn read_versioned(&self) -> VersionedRef<'_, U> {
+        VersionedRef {
+            state: self.read(),
+            version: self.version(),
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use super::*;
+use std::rc::Weak;
+
+/// A `WeakAppUniverse` is a handle to a universe that doesn't keep it alive.
+///
+/// It's meant to be captured by closures the universe itself owns (such as subscribers), where a
+/// regular clone would create a reference cycle. It's obtained from `AppUniverse::downgrade`.
+pub struct WeakAppUniverse<U: AppUniverseCore>(Weak<UniverseInner<U>>);
+
+impl<U: AppUniverseCore> WeakAppUniverse<U> {
+    /// Returns a regular handle to the universe, or `None` if every regular handle has been
+    /// dropped.
+    pub fn upgrade(&self) -> Option<AppUniverse<U>> {
+        Some(AppUniverse {
+            inner: self.0.upgrade()?,
+        })
+    }
+}
+
+impl<U: AppUniverseCore> AppUniverse<U> {
+    /// Returns a `WeakAppUniverse` for this universe.
+    pub fn downgrade(&self) -> WeakAppUniverse<U> {
+        WeakAppUniverse(Rc::downgrade(&self.inner))
+    }
+}
+
+impl<U: AppUniverseCore + 'static> AppUniverse<U> {
+    /// This function takes a subscriber function that runs anytime the state changes and receives a
+    /// `WeakAppUniverse`.
+    ///
+    /// This is the recommended way to subscribe when the subscriber dispatches. Capturing a regular
+    /// clone of the universe in a subscriber creates a reference cycle and the universe is never
+    /// freed, while the weak handle only has to be upgraded for as long as the dispatch takes:
+    ///
+    /// ```rust
+    /// # use app_universe::{AppUniverse, AppUniverseCore};
+    /// # struct Counter { value: u32 }
+    /// # impl AppUniverseCore for Counter {
+    /// #     type Message = u32;
+    /// #     fn msg(&mut self, amount: u32) { self.value += amount; }
+    /// # }
+    /// let mut universe = AppUniverse::new(Counter { value: 0 });
+    ///
+    /// universe.subscribe_weak_self(Box::new(|weak_universe| {
+    ///     if let Some(universe) = weak_universe.upgrade() {
+    ///         if universe.read().value % 10 != 0 {
+    ///             universe.msg(1);
+    ///         }
+    ///     }
+    /// }));
+    ///
+    /// universe.msg(1);
+    /// assert_eq!(universe.read().value, 10);
+    /// ```
+    pub fn subscribe_weak_self(
+        &mut self,
+        mut subscriber_fn: Box<dyn FnMut(WeakAppUniverse<U>)>,
+    ) -> UniverseSubscription<U> {
+        self.subscribe_with_ctx(Box::new(move |ctx| subscriber_fn(ctx.universe())))
+    }
+}
+
+impl<U: AppUniverseCore> Clone for WeakAppUniverse<U> {
+    fn clone(&self) -> Self {
+        WeakAppUniverse(self.0.clone())
+    }
+}
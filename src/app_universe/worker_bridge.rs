@@ -0,0 +1,312 @@
+//! Runs an `AppUniverse` inside a web worker while the UI on the main thread talks to it
+//! through a `WorkerUniverseClient`.
+//!
+//! The host serializes a snapshot of the state every time its subscribers are notified and
+//! posts it to the client. The client posts serialized messages back. Both sides exchange
+//! JSON strings through a `WorkerPort`, which is implemented for `web_sys::Worker` (the main
+//! thread side) and `web_sys::DedicatedWorkerGlobalScope` (the worker side).
+
+use super::{AppUniverse, AppUniverseCore, SubscriptionGuard};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    cell::{Ref, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+/// Something that JSON payloads can be posted to.
+pub trait WorkerPort {
+    /// Posts `payload` to the other side of the bridge.
+    fn post(&self, payload: &str);
+}
+
+impl WorkerPort for web_sys::Worker {
+    fn post(&self, payload: &str) {
+        self.post_message(&JsValue::from_str(payload))
+            .expect("Failed to post a message to the worker");
+    }
+}
+
+impl WorkerPort for web_sys::DedicatedWorkerGlobalScope {
+    fn post(&self, payload: &str) {
+        self.post_message(&JsValue::from_str(payload))
+            .expect("Failed to post a message from the worker");
+    }
+}
+
+// Everything the host sends to the client.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind")]
+enum HostEnvelope<S> {
+    Ready,
+    Snapshot { version: u64, state: S },
+}
+
+type OnMessage = Closure<dyn FnMut(web_sys::MessageEvent)>;
+
+/// The `WorkerUniverseHost` lives inside the web worker and owns the real universe.
+///
+/// Whenever the universe notifies its subscribers, the host posts a snapshot of the state to the
+/// client. Messages received from the client are dispatched with `msg` in the order they arrive.
+/// Dropping the host removes its subscription from the universe.
+pub struct WorkerUniverseHost<U: AppUniverseCore + 'static> {
+    universe: AppUniverse<U>,
+    port: Rc<dyn WorkerPort>,
+    _subscription: SubscriptionGuard<U>,
+    _on_message: Option<OnMessage>,
+}
+
+impl<U> WorkerUniverseHost<U>
+where
+    U: AppUniverseCore + Serialize + 'static,
+    U::Message: DeserializeOwned,
+{
+    /// Creates a host that posts snapshots to `port`.
+    ///
+    /// The client is told that the host is ready and receives the current state straight away.
+    pub fn new(mut universe: AppUniverse<U>, port: impl WorkerPort + 'static) -> Self {
+        let port: Rc<dyn WorkerPort> = Rc::new(port);
+
+        port.post(&ready_payload());
+        post_snapshot(&*port, &universe);
+
+        let subscription_port = port.clone();
+        let subscription = universe.subscribe_guarded(move |universe| {
+            post_snapshot(&*subscription_port, &universe);
+        });
+
+        WorkerUniverseHost {
+            universe,
+            port,
+            _subscription: subscription,
+            _on_message: None,
+        }
+    }
+
+    /// Creates a host that talks to the main thread through the global scope of the current worker.
+    ///
+    /// This must be called from inside a dedicated web worker.
+    pub fn attach_to_worker_scope(universe: AppUniverse<U>) -> Self {
+        let scope: web_sys::DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+        let mut host = WorkerUniverseHost::new(universe, scope.clone());
+
+        let receiving_universe = host.universe.clone();
+        let on_message = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+            if let Some(payload) = event.data().as_string() {
+                // A message that can't be decoded has nowhere to be reported to, so it is dropped.
+                let _ = receive_message(&receiving_universe, &payload);
+            }
+        }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+
+        scope.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        host._on_message = Some(on_message);
+
+        host
+    }
+
+    /// Dispatches a serialized message that was received from the client.
+    pub fn receive(&self, payload: &str) -> Result<(), serde_json::Error> {
+        receive_message(&self.universe, payload)
+    }
+
+    /// Returns the universe owned by this host.
+    pub fn universe(&self) -> &AppUniverse<U> {
+        &self.universe
+    }
+
+    /// Posts the current state to the client without waiting for a notification.
+    pub fn post_snapshot(&self) {
+        post_snapshot(&*self.port, &self.universe);
+    }
+}
+
+fn ready_payload() -> String {
+    serde_json::to_string(&HostEnvelope::<()>::Ready).expect("Failed to serialize ready message")
+}
+
+fn post_snapshot<U>(port: &dyn WorkerPort, universe: &AppUniverse<U>)
+where
+    U: AppUniverseCore + Serialize + 'static,
+{
+    let payload = serde_json::to_string(&HostEnvelope::Snapshot {
+        version: universe.version(),
+        state: &*universe.read(),
+    })
+    .expect("Failed to serialize the universe state");
+
+    port.post(&payload);
+}
+
+fn receive_message<U>(universe: &AppUniverse<U>, payload: &str) -> Result<(), serde_json::Error>
+where
+    U: AppUniverseCore + 'static,
+    U::Message: DeserializeOwned,
+{
+    let message = serde_json::from_str(payload)?;
+    universe.msg(message);
+    Ok(())
+}
+
+type ClientSubscriber<U> = Box<dyn FnMut(&WorkerUniverseClient<U>)>;
+
+type ClientSubscriberParameter<U> = Rc<RefCell<ClientSubscriber<U>>>;
+
+/// The `WorkerClientSubscription` is returned by `WorkerUniverseClient::subscribe`. Its only
+/// purpose is to be passed into `WorkerUniverseClient::unsubscribe`.
+#[must_use = "ignoring the subscription handle means you can never unsubscribe"]
+pub struct WorkerClientSubscription<U: AppUniverseCore>(ClientSubscriberParameter<U>);
+
+struct ClientState<U: AppUniverseCore> {
+    ready: bool,
+    pending: Vec<U::Message>,
+    snapshot: Option<U>,
+    version: u64,
+}
+
+/// The `WorkerUniverseClient` is the main thread side of the bridge.
+///
+/// It sends messages to the host and keeps the latest snapshot it received. Messages that are sent
+/// before the host reported that it is ready are buffered and flushed in order once it is.
+///
+/// Cloning the client is cheap and all clones share the same snapshot and subscribers.
+pub struct WorkerUniverseClient<U: AppUniverseCore> {
+    port: Rc<dyn WorkerPort>,
+    state: Rc<RefCell<ClientState<U>>>,
+    subscribers: Rc<RefCell<Vec<ClientSubscriberParameter<U>>>>,
+    _on_message: Option<Rc<OnMessage>>,
+}
+
+impl<U> WorkerUniverseClient<U>
+where
+    U: AppUniverseCore + DeserializeOwned + 'static,
+    U::Message: Serialize,
+{
+    /// Creates a client that posts messages to `port`.
+    /// Payloads from the host have to be passed to `receive`.
+    pub fn new(port: impl WorkerPort + 'static) -> Self {
+        WorkerUniverseClient {
+            port: Rc::new(port),
+            state: Rc::new(RefCell::new(ClientState {
+                ready: false,
+                pending: vec![],
+                snapshot: None,
+                version: 0,
+            })),
+            subscribers: Rc::new(RefCell::new(vec![])),
+            _on_message: None,
+        }
+    }
+
+    /// Starts the worker script at `script_url` and connects a client to it.
+    pub fn spawn(script_url: &str) -> Result<Self, JsValue> {
+        let worker = web_sys::Worker::new(script_url)?;
+        let mut client = WorkerUniverseClient::new(worker.clone());
+
+        let receiving_client = client.clone();
+        let on_message = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+            if let Some(payload) = event.data().as_string() {
+                // A snapshot that can't be decoded is dropped, the next one replaces it anyway.
+                let _ = receiving_client.receive(&payload);
+            }
+        }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        client._on_message = Some(Rc::new(on_message));
+
+        Ok(client)
+    }
+
+    /// Sends a message to the universe in the worker.
+    ///
+    /// If the worker isn't ready yet the message is buffered.
+    pub fn msg(&self, message: U::Message) -> Result<(), serde_json::Error> {
+        let mut state = self.state.borrow_mut();
+
+        if state.ready {
+            self.port.post(&serde_json::to_string(&message)?);
+        } else {
+            state.pending.push(message);
+        }
+
+        Ok(())
+    }
+
+    /// Handles a payload that was posted by the host.
+    ///
+    /// Subscribers are called whenever a new snapshot arrives.
+    pub fn receive(&self, payload: &str) -> Result<(), serde_json::Error> {
+        match serde_json::from_str::<HostEnvelope<U>>(payload)? {
+            HostEnvelope::Ready => {
+                let pending = {
+                    let mut state = self.state.borrow_mut();
+                    state.ready = true;
+                    std::mem::take(&mut state.pending)
+                };
+
+                for message in pending {
+                    self.port.post(&serde_json::to_string(&message)?);
+                }
+            }
+            HostEnvelope::Snapshot { version, state } => {
+                {
+                    let mut client_state = self.state.borrow_mut();
+                    client_state.snapshot = Some(state);
+                    client_state.version = version;
+                }
+
+                let subscribers = self.subscribers.borrow().clone();
+                for subscriber in subscribers {
+                    (subscriber.borrow_mut())(self);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Acquire read access to the latest snapshot, or `None` if no snapshot has arrived yet.
+    pub fn read(&self) -> Option<Ref<'_, U>> {
+        Ref::filter_map(self.state.borrow(), |state| state.snapshot.as_ref()).ok()
+    }
+
+    /// Returns the version of the universe that produced the latest snapshot.
+    pub fn version(&self) -> u64 {
+        self.state.borrow().version
+    }
+
+    /// Returns `true` once the host has reported that it is ready.
+    pub fn is_ready(&self) -> bool {
+        self.state.borrow().ready
+    }
+
+    /// This function takes a subscriber function that runs whenever a new snapshot arrives.
+    pub fn subscribe(&self, subscriber_fn: ClientSubscriber<U>) -> WorkerClientSubscription<U> {
+        let subscriber = Rc::new(RefCell::new(subscriber_fn));
+        self.subscribers.borrow_mut().push(subscriber.clone());
+        WorkerClientSubscription(subscriber)
+    }
+
+    /// This function removes `subscription` and returns whether it was still registered.
+    ///
+    /// Subscribers can unsubscribe while being notified, the removal applies from the next
+    /// snapshot on.
+    pub fn unsubscribe(&self, subscription: WorkerClientSubscription<U>) -> bool {
+        let mut subscribers = self.subscribers.borrow_mut();
+        let len_before = subscribers.len();
+
+        subscribers.retain(|subscriber| !Rc::ptr_eq(subscriber, &subscription.0));
+
+        len_before != subscribers.len()
+    }
+}
+
+impl<U: AppUniverseCore> Clone for WorkerUniverseClient<U> {
+    fn clone(&self) -> Self {
+        WorkerUniverseClient {
+            port: self.port.clone(),
+            state: self.state.clone(),
+            subscribers: self.subscribers.clone(),
+            _on_message: self._on_message.clone(),
+        }
+    }
+}
@@ -32,9 +32,9 @@
 //!
 //!     universe.msg(Msg::Increment(1));
 //!
-//!     let subscription = universe.subscribe(Box::new(move |universe| {
+//!     let subscription = universe.subscribe(move |universe| {
 //!         println!("Counter value is {}", universe.read().counter);
-//!     }));
+//!     });
 //!
 //!     universe.msg(Msg::Increment(1));
 //!
@@ -188,12 +188,11 @@
 //!     let core = MyAppState { cart: vec![] };
 //!     let mut universe = AppUniverse::new(core);
 //!     
-//!     let subscription = universe.subscribe(Box::new(|universe| { /* Do something */ }));
+//!     let subscription = universe.subscribe(|universe| { /* Do something */ });
 //! }
 //! ```
 
 mod app_universe;
+#[allow(clippy::module_inception)]
 mod tests;
 pub use crate::app_universe::*;
-
-// I want the subscription to be removed when the subscriptions go out of scope
@@ -3,10 +3,12 @@ mod tests {
     use crate::app_universe::*;
     use std::rc::Rc;
 
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     struct TestAppState {
         counter: u8,
     }
 
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Msg {
         Increment(u8),
     }
@@ -43,7 +45,7 @@ mod tests {
 
         let mut universe = AppUniverse::new(state);
 
-        universe.subscribe(Box::new(move |universe| {
+        let _ = universe.subscribe_boxed(Box::new(move |universe| {
             let c = universe.read().counter;
             *some_value_clone.borrow_mut() += c;
         }));
@@ -63,7 +65,7 @@ mod tests {
 
         let mut universe = AppUniverse::new(state);
 
-        let subscription = universe.subscribe(Box::new(move |universe| {
+        let subscription = universe.subscribe_boxed(Box::new(move |universe| {
             let c = universe.read().counter;
             *some_value_clone.borrow_mut() += c;
         }));
@@ -89,13 +91,13 @@ mod tests {
 
         let mut universe = AppUniverse::new(state);
 
-        let increment_counter_by_two_subscription = universe.subscribe(Box::new(move |_| {
+        let increment_counter_by_two_subscription = universe.subscribe_boxed(Box::new(move |_| {
             *some_value_clone.borrow_mut() += 2;
         }));
 
         let some_value_clone = some_value.clone();
 
-        universe.subscribe(Box::new(move |universe| {
+        let _ = universe.subscribe_boxed(Box::new(move |universe| {
             let c = universe.read().counter;
             *some_value_clone.borrow_mut() += c;
         }));
@@ -118,4 +120,3405 @@ mod tests {
         assert_eq!(universe.read_subscriptions().len(), 1);
     }
     /* TODO Expose some methods to test the number of subs when unsubs are made*/
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn wait_for_change_wakes_every_waiter() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let first_waiter = universe.wait_for_change();
+        let second_waiter = universe.wait_for_change();
+
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(futures::executor::block_on(first_waiter), 1);
+        assert_eq!(futures::executor::block_on(second_waiter), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn wait_for_change_ignores_earlier_changes() {
+        use futures::task::noop_waker_ref;
+        use std::future::Future;
+        use std::task::{Context, Poll};
+
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.msg(Msg::Increment(1));
+
+        let mut waiter = Box::pin(universe.wait_for_change());
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        assert_eq!(waiter.as_mut().poll(&mut cx), Poll::Pending);
+
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(waiter.as_mut().poll(&mut cx), Poll::Ready(2));
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn test_set_state_does_not_notify() {
+        use std::cell::RefCell;
+
+        let calls = Rc::new(RefCell::new(0));
+        let calls_clone = calls.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.subscribe_forever(move |_| {
+            *calls_clone.borrow_mut() += 1;
+        });
+
+        universe.test_set_state(|state| state.counter = 42);
+
+        assert_eq!(universe.read().counter, 42);
+        assert_eq!(universe.version(), 0);
+        assert_eq!(*calls.borrow(), 0);
+    }
+
+    #[test]
+    fn polling_only_reports_real_changes() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let mut cursor = ChangeCursor::new();
+        let mut rebuilds = vec![];
+
+        for frame in 0..6 {
+            if frame == 2 || frame == 4 {
+                universe.msg(Msg::Increment(1));
+                universe.msg(Msg::Increment(1));
+            }
+
+            if let Some(state) = universe.read_if_changed(&mut cursor) {
+                rebuilds.push((frame, state.counter));
+            }
+        }
+
+        assert_eq!(rebuilds, vec![(0, 0), (2, 2), (4, 4)]);
+        assert_eq!(cursor.last_seen(), Some(4));
+        assert!(!universe.changed_since(4));
+        assert!(universe.changed_since(3));
+
+        let mut cursor = universe.change_cursor();
+
+        assert!(!universe.poll_changes(&mut cursor));
+    }
+
+    #[test]
+    fn extend_notifies_once() {
+        use std::cell::RefCell;
+
+        let calls = Rc::new(RefCell::new(0));
+        let calls_clone = calls.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.subscribe_forever(move |_| {
+            *calls_clone.borrow_mut() += 1;
+        });
+
+        universe.extend((0..5).map(|_| Msg::Increment(1)));
+
+        assert_eq!(universe.read().counter, 5);
+        assert_eq!(universe.version(), 5);
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    #[cfg(feature = "worker-bridge")]
+    #[derive(Clone, Default)]
+    struct RecordingPort(Rc<std::cell::RefCell<Vec<String>>>);
+
+    #[cfg(feature = "worker-bridge")]
+    impl WorkerPort for RecordingPort {
+        fn post(&self, payload: &str) {
+            self.0.borrow_mut().push(payload.to_string());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "worker-bridge")]
+    fn worker_bridge_round_trip() {
+        let to_host = RecordingPort::default();
+        let to_client = RecordingPort::default();
+
+        let client = WorkerUniverseClient::<TestAppState>::new(to_host.clone());
+
+        // Sent before the host is ready, so these are buffered.
+        client.msg(Msg::Increment(1)).unwrap();
+        client.msg(Msg::Increment(2)).unwrap();
+        assert!(to_host.0.borrow().is_empty());
+        assert!(client.read().is_none());
+
+        let host = WorkerUniverseHost::new(
+            AppUniverse::new(TestAppState { counter: 0 }),
+            to_client.clone(),
+        );
+
+        for payload in to_client.0.borrow_mut().drain(..) {
+            client.receive(&payload).unwrap();
+        }
+        assert!(client.is_ready());
+        assert_eq!(client.read().unwrap().counter, 0);
+
+        for payload in to_host.0.borrow_mut().drain(..) {
+            host.receive(&payload).unwrap();
+        }
+        assert_eq!(host.universe().read().counter, 3);
+
+        let notified = Rc::new(std::cell::RefCell::new(vec![]));
+        let notified_clone = notified.clone();
+        let subscription = client.subscribe(Box::new(move |client| {
+            notified_clone
+                .borrow_mut()
+                .push((client.version(), client.read().unwrap().counter));
+        }));
+
+        for payload in to_client.0.borrow_mut().drain(..) {
+            client.receive(&payload).unwrap();
+        }
+
+        assert_eq!(*notified.borrow(), vec![(1, 1), (2, 3)]);
+        assert!(client.unsubscribe(subscription));
+
+        let universe = host.universe().clone();
+        assert_eq!(universe.subscriber_count(), 1);
+        drop(host);
+        assert_eq!(universe.subscriber_count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn with_subscription_capacity_preallocates() {
+        let mut universe = AppUniverse::with_subscription_capacity(TestAppState { counter: 0 }, 8);
+
+        assert!(universe.read_subscriptions().capacity() >= 8);
+
+        universe.subscribe_forever(|_| {});
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(universe.read_subscriptions().len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn hydration_round_trip() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.msg(Msg::Increment(7));
+
+        let payload = universe.dehydrate().unwrap();
+        let hydrated = AppUniverse::<TestAppState>::hydrate(&payload).unwrap();
+
+        assert_eq!(hydrated.read().counter, 7);
+        assert_eq!(hydrated.version(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn hydration_falls_back_to_fresh_core() {
+        let missing = AppUniverse::hydrate_into(TestAppState { counter: 1 }, None);
+        assert_eq!(missing.read().counter, 1);
+
+        let malformed = AppUniverse::hydrate_into(TestAppState { counter: 2 }, Some("{"));
+        assert_eq!(malformed.read().counter, 2);
+
+        let incompatible_payload = r#"{"format":999,"state":{"counter":5}}"#;
+        assert!(matches!(
+            AppUniverse::<TestAppState>::hydrate(incompatible_payload),
+            Err(HydrateError::IncompatibleVersion { found: 999, .. })
+        ));
+
+        let incompatible =
+            AppUniverse::hydrate_into(TestAppState { counter: 3 }, Some(incompatible_payload));
+        assert_eq!(incompatible.read().counter, 3);
+    }
+
+    struct Product {
+        id: u16,
+    }
+
+    struct CartState {
+        cart: Vec<Product>,
+    }
+
+    enum CartMsg {
+        AddProductToCart(Product),
+    }
+
+    impl AppUniverseCore for CartState {
+        type Message = CartMsg;
+
+        fn msg(&mut self, message: Self::Message) {
+            match message {
+                CartMsg::AddProductToCart(product) => {
+                    self.cart.push(product);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn dispatch_mapped_loads_products_in_one_batch() {
+        use std::cell::RefCell;
+
+        let calls = Rc::new(RefCell::new(0));
+        let calls_clone = calls.clone();
+
+        let mut universe = AppUniverse::new(CartState { cart: vec![] });
+
+        universe.subscribe_forever(move |_| {
+            *calls_clone.borrow_mut() += 1;
+        });
+
+        universe.dispatch_mapped(vec![1, 2, 3], |id| {
+            CartMsg::AddProductToCart(Product { id })
+        });
+
+        let ids: Vec<u16> = universe
+            .read()
+            .cart
+            .iter()
+            .map(|product| product.id)
+            .collect();
+
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    #[test]
+    fn follow_up_messages_inherit_the_dispatch_id() {
+        use std::cell::RefCell;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.subscribe_forever(move |universe| {
+            seen_clone
+                .borrow_mut()
+                .push((universe.read().counter, universe.current_dispatch_id()));
+
+            if universe.read().counter == 1 {
+                universe.msg(Msg::Increment(1));
+            }
+        });
+
+        let id = universe.msg_traced(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(
+            *seen.borrow(),
+            vec![(1, Some(id)), (2, Some(id)), (3, None)]
+        );
+        assert_eq!(universe.current_dispatch_id(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_follow_up_messages_inherit_the_dispatch_id() {
+        use std::cell::RefCell;
+
+        let spawned: Rc<RefCell<Vec<LocalBoxFuture>>> = Rc::new(RefCell::new(vec![]));
+        let spawned_clone = spawned.clone();
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.set_spawner(move |future| spawned_clone.borrow_mut().push(future));
+
+        universe.subscribe_forever(move |universe| {
+            seen_clone
+                .borrow_mut()
+                .push((universe.read().counter, universe.current_dispatch_id()));
+
+            if universe.read().counter == 1 {
+                universe
+                    .dispatch_async(async { Msg::Increment(5) })
+                    .unwrap();
+            }
+        });
+
+        let id = universe.msg_traced(Msg::Increment(1));
+
+        let futures: Vec<LocalBoxFuture> = spawned.borrow_mut().drain(..).collect();
+        for future in futures {
+            futures::executor::block_on(future);
+        }
+
+        assert_eq!(*seen.borrow(), vec![(1, Some(id)), (6, Some(id))]);
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn dispatch_async_tracked_reports_status_transitions() {
+        use std::cell::RefCell;
+
+        let spawned: Rc<RefCell<Vec<LocalBoxFuture>>> = Rc::new(RefCell::new(vec![]));
+        let spawned_clone = spawned.clone();
+
+        let statuses = Rc::new(RefCell::new(vec![]));
+        let statuses_clone = statuses.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.set_spawner(move |future| spawned_clone.borrow_mut().push(future));
+
+        universe.subscribe_forever(move |universe| {
+            statuses_clone
+                .borrow_mut()
+                .push((universe.async_status("load"), universe.async_status("fail")));
+        });
+
+        universe
+            .dispatch_async_tracked("load", async { Ok::<_, String>(Msg::Increment(2)) })
+            .unwrap();
+        universe
+            .dispatch_async_tracked("fail", async { Err::<Msg, _>("offline") })
+            .unwrap();
+
+        assert_eq!(universe.async_status("load"), Some(AsyncStatus::Loading));
+        assert_eq!(universe.async_status("missing"), None);
+
+        let futures: Vec<LocalBoxFuture> = spawned.borrow_mut().drain(..).collect();
+        for future in futures {
+            futures::executor::block_on(future);
+        }
+
+        assert_eq!(universe.read().counter, 2);
+        assert_eq!(
+            *statuses.borrow(),
+            vec![
+                (Some(AsyncStatus::Loading), None),
+                (Some(AsyncStatus::Loading), Some(AsyncStatus::Loading)),
+                (Some(AsyncStatus::Loaded), Some(AsyncStatus::Loading)),
+                (
+                    Some(AsyncStatus::Loaded),
+                    Some(AsyncStatus::Failed("offline".to_string()))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "gtk")]
+    fn glib_bridge_dispatches_and_notifies_on_main_context() {
+        use std::cell::RefCell;
+        use std::thread;
+
+        let main_context = glib::MainContext::new();
+
+        main_context
+            .with_thread_default(|| {
+                let main_thread = thread::current().id();
+                let universe = AppUniverse::new(TestAppState { counter: 0 });
+                let mut bridge = GlibUniverseBridge::attach(universe.clone(), &main_context);
+
+                let notified_on = Rc::new(RefCell::new(vec![]));
+                let notified_on_clone = notified_on.clone();
+                let _ = bridge.subscribe_on_main(move |universe| {
+                    notified_on_clone
+                        .borrow_mut()
+                        .push((thread::current().id(), universe.read().counter));
+                });
+
+                let sender = bridge.sender();
+                thread::spawn(move || {
+                    assert!(sender.send(Msg::Increment(1)).is_ok());
+                    assert!(sender.send(Msg::Increment(2)).is_ok());
+                })
+                .join()
+                .unwrap();
+
+                while notified_on.borrow().is_empty() {
+                    main_context.iteration(true);
+                }
+
+                assert_eq!(universe.read().counter, 3);
+                assert_eq!(*notified_on.borrow(), vec![(main_thread, 3)]);
+
+                let sender = bridge.sender();
+                drop(bridge);
+                while main_context.pending() {
+                    main_context.iteration(false);
+                }
+
+                assert!(sender.send(Msg::Increment(1)).is_err());
+            })
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn subscribe_unique_does_not_add_duplicates() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        assert!(universe.subscribe_unique("logger", |_| {}).is_some());
+        assert!(universe.subscribe_unique("logger", |_| {}).is_none());
+
+        assert_eq!(universe.read_subscriptions().len(), 1);
+    }
+
+    #[test]
+    fn subscribe_unique_works_from_a_subscriber() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let added = Rc::new(std::cell::Cell::new(0));
+        let added_clone = added.clone();
+
+        universe.subscribe_forever(move |mut universe| {
+            for _ in 0..2 {
+                if universe.subscribe_unique("logger", |_| {}).is_some() {
+                    added_clone.set(added_clone.get() + 1);
+                }
+            }
+        });
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(added.get(), 1);
+        assert_eq!(universe.subscriber_count(), 2);
+    }
+
+    #[test]
+    fn microtask_strategy_notifies_once_per_flush() {
+        use std::cell::RefCell;
+
+        let calls = Rc::new(RefCell::new(vec![]));
+        let calls_clone = calls.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.set_notification_strategy(NotificationStrategy::Microtask);
+
+        universe.subscribe_forever(move |universe| {
+            let counter = universe.read().counter;
+            calls_clone.borrow_mut().push(counter);
+
+            if counter == 3 {
+                universe.msg(Msg::Increment(1));
+            }
+        });
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(universe.read().counter, 3);
+        assert!(calls.borrow().is_empty());
+
+        universe.flush();
+
+        // The dispatch from inside the notification is applied but its notification is deferred.
+        assert_eq!(universe.read().counter, 4);
+        assert_eq!(*calls.borrow(), vec![3]);
+
+        universe.flush();
+        universe.flush();
+
+        assert_eq!(*calls.borrow(), vec![3, 4]);
+    }
+
+    #[test]
+    fn dispatch_fold_reads_state_between_steps() {
+        use std::cell::RefCell;
+
+        let calls = Rc::new(RefCell::new(0));
+        let calls_clone = calls.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 1 });
+
+        universe.subscribe_forever(move |_| {
+            *calls_clone.borrow_mut() += 1;
+        });
+
+        // Every step doubles the counter it finds, then adds the item.
+        universe.dispatch_fold(0, vec![1, 2], |state, item| {
+            Msg::Increment(state.counter + item)
+        });
+
+        assert_eq!(universe.read().counter, 12);
+        assert_eq!(universe.version(), 3);
+        assert_eq!(*calls.borrow(), 1);
+    }
+
+    #[test]
+    fn fallible_subscriber_errors_reach_the_handler() {
+        use std::cell::RefCell;
+        use std::fmt;
+
+        #[derive(Debug)]
+        struct OddCounter(u8);
+
+        impl fmt::Display for OddCounter {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "counter {} is odd", self.0)
+            }
+        }
+
+        impl std::error::Error for OddCounter {}
+
+        let errors = Rc::new(RefCell::new(vec![]));
+        let errors_clone = errors.clone();
+        let later_calls = Rc::new(RefCell::new(0));
+        let later_calls_clone = later_calls.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.set_subscriber_error_handler(move |id, error| {
+            errors_clone.borrow_mut().push((id, error.to_string()));
+        });
+
+        let subscription = universe.subscribe_fallible(|universe| {
+            let counter = universe.read().counter;
+            if counter % 2 == 1 {
+                Err(OddCounter(counter))
+            } else {
+                Ok(())
+            }
+        });
+
+        universe.subscribe_forever(move |_| {
+            *later_calls_clone.borrow_mut() += 1;
+        });
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+
+        let id = subscription.id();
+        assert_eq!(
+            *errors.borrow(),
+            vec![
+                (id, "counter 1 is odd".to_string()),
+                (id, "counter 3 is odd".to_string())
+            ]
+        );
+        assert_eq!(*later_calls.borrow(), 3);
+    }
+
+    #[test]
+    fn reprioritize_changes_notification_order() {
+        use std::cell::RefCell;
+
+        let order = Rc::new(RefCell::new(vec![]));
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let mut subscriptions = vec![];
+        for name in ["logger", "view", "cache"] {
+            let order = order.clone();
+            subscriptions.push(universe.subscribe(move |_| {
+                order.borrow_mut().push(name);
+            }));
+        }
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*order.borrow(), vec!["logger", "view", "cache"]);
+
+        universe
+            .reprioritize(&subscriptions[0], SubscriberPriority::Last)
+            .unwrap();
+        universe
+            .reprioritize(&subscriptions[2], SubscriberPriority::Value(-1))
+            .unwrap();
+
+        order.borrow_mut().clear();
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*order.borrow(), vec!["cache", "view", "logger"]);
+
+        let other_subscription = AppUniverse::new(TestAppState { counter: 0 }).subscribe(|_| {});
+        assert_eq!(
+            universe.reprioritize(&other_subscription, SubscriberPriority::First),
+            Err(UniverseError::SubscriptionNotFound)
+        );
+    }
+
+    #[test]
+    fn reprioritize_reaches_subscriptions_changed_while_notifying() {
+        use std::cell::RefCell;
+
+        let order = Rc::new(RefCell::new(vec![]));
+        let results = Rc::new(RefCell::new(vec![]));
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let cache_order = order.clone();
+        let _cache = universe.subscribe(move |_| cache_order.borrow_mut().push("cache"));
+        let view_order = order.clone();
+        let view = Rc::new(
+            universe.subscribe_keyed("view", move |_| view_order.borrow_mut().push("view")),
+        );
+
+        let (late_order, view, late_results) = (order.clone(), view.clone(), results.clone());
+        let _ = universe.subscribe_once(move |mut universe| {
+            // Subscribed during the pass, so it only joins once the pass is over.
+            let late = universe.subscribe(move |_| late_order.borrow_mut().push("late"));
+            late_results
+                .borrow_mut()
+                .push(universe.reprioritize(&late, SubscriberPriority::First));
+
+            // Unsubscribed during the pass, but still kept until the pass is over.
+            universe.unsubscribe_key("view");
+            late_results
+                .borrow_mut()
+                .push(universe.reprioritize(&view, SubscriberPriority::Last));
+        });
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(
+            *results.borrow(),
+            vec![Ok(()), Err(UniverseError::SubscriptionNotFound)]
+        );
+
+        order.borrow_mut().clear();
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*order.borrow(), vec!["late", "cache"]);
+    }
+
+    #[test]
+    fn replay_mapped_migrates_a_message_log() {
+        struct Tally {
+            total: u32,
+        }
+
+        enum TallyMsg {
+            Add(u32),
+        }
+
+        impl AppUniverseCore for Tally {
+            type Message = TallyMsg;
+
+            fn msg(&mut self, message: Self::Message) {
+                match message {
+                    TallyMsg::Add(amount) => self.total += amount,
+                }
+            }
+        }
+
+        let log = vec![Msg::Increment(2), Msg::Increment(0), Msg::Increment(5)];
+
+        let universe =
+            AppUniverse::<TestAppState>::replay_mapped(Tally { total: 0 }, log, |msg| match msg {
+                Msg::Increment(0) => None,
+                Msg::Increment(value) => Some(TallyMsg::Add(value.into())),
+            });
+
+        assert_eq!(universe.read().total, 7);
+        assert_eq!(universe.version(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "iced")]
+    fn iced_recipe_yields_versions_on_dispatch() {
+        use futures::StreamExt;
+        use iced_futures::subscription::{Hasher, Recipe};
+        use std::hash::Hasher as _;
+
+        fn recipe_hash(universe: &AppUniverse<TestAppState>) -> u64 {
+            let mut hasher = Hasher::default();
+            UniverseSubscriptionRecipe::new(universe).hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let other_universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        assert_eq!(recipe_hash(&universe), recipe_hash(&universe.clone()));
+        assert_ne!(recipe_hash(&universe), recipe_hash(&other_universe));
+
+        let mut versions = Box::new(UniverseSubscriptionRecipe::new(&universe))
+            .stream(futures::stream::empty().boxed());
+
+        let _: iced_runtime::Task<()> = forward_to_universe(&universe, Msg::Increment(1));
+        universe.msg_batch(vec![Msg::Increment(1), Msg::Increment(1)]);
+
+        assert_eq!(futures::executor::block_on(versions.next()), Some(1));
+        assert_eq!(futures::executor::block_on(versions.next()), Some(3));
+        assert_eq!(universe.read().counter, 3);
+        assert_eq!(universe.subscriber_count(), 1);
+
+        drop(versions);
+        universe.msg(Msg::Increment(1));
+        assert_eq!(universe.subscriber_count(), 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "futures-signals", feature = "test-utils"))]
+    fn signal_follows_dispatches_and_unsubscribes_on_drop() {
+        use futures::StreamExt;
+        use futures_signals::signal::SignalExt;
+
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let mut halves = universe
+            .signal(|state: &TestAppState| state.counter / 2)
+            .to_stream();
+
+        assert_eq!(futures::executor::block_on(halves.next()), Some(0));
+        universe.msg(Msg::Increment(2));
+        assert_eq!(futures::executor::block_on(halves.next()), Some(1));
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+        assert_eq!(futures::executor::block_on(halves.next()), Some(2));
+        assert_eq!(universe.read_subscriptions().len(), 1);
+
+        drop(halves);
+        assert_eq!(universe.read_subscriptions().len(), 0);
+    }
+
+    #[test]
+    #[cfg(all(feature = "futures-signals", feature = "test-utils"))]
+    fn signal_vec_sends_minimal_diffs() {
+        use futures::StreamExt;
+        use futures_signals::signal_vec::{SignalVecExt, VecDiff};
+
+        struct Todos {
+            items: Vec<&'static str>,
+        }
+
+        impl AppUniverseCore for Todos {
+            type Message = Vec<&'static str>;
+
+            fn msg(&mut self, items: Self::Message) {
+                self.items = items;
+            }
+        }
+
+        let universe = AppUniverse::new(Todos { items: vec!["a"] });
+        let mut diffs = universe
+            .signal_vec(|state: &Todos| state.items.clone())
+            .to_stream();
+        let mut next = || futures::executor::block_on(diffs.next()).unwrap();
+
+        assert_eq!(next(), VecDiff::Replace { values: vec!["a"] });
+        universe.msg(vec!["a", "b"]);
+        assert_eq!(next(), VecDiff::Push { value: "b" });
+        universe.msg(vec!["a", "c"]);
+        assert_eq!(
+            next(),
+            VecDiff::UpdateAt {
+                index: 1,
+                value: "c"
+            }
+        );
+        universe.msg(vec!["a"]);
+        assert_eq!(next(), VecDiff::Pop {});
+        universe.msg(vec!["b", "c", "d"]);
+        assert_eq!(
+            next(),
+            VecDiff::Replace {
+                values: vec!["b", "c", "d"]
+            }
+        );
+
+        drop(diffs);
+        assert_eq!(universe.read_subscriptions().len(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn notification_log_records_subscriber_schedule() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let _ = universe.subscribe_labeled("render", |_| {});
+        let unlabeled = universe.subscribe(|_| {});
+        let _ = universe.subscribe_with_priority(|_| {}, SubscriberPriority::First);
+
+        universe.msg(Msg::Increment(1));
+
+        let log = universe.start_recording();
+        universe.msg(Msg::Increment(1));
+        universe.msg_batch(vec![Msg::Increment(1), Msg::Increment(1)]);
+        universe.stop_recording();
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(unlabeled.label(), None);
+        assert_eq!(
+            log.entries()[1],
+            SubscriberInvocation {
+                subscription_label: String::from("render"),
+                version: 2,
+                ordinal: 1,
+            }
+        );
+        assert_eq!(
+            log.render(),
+            "0 v2 #3\n1 v2 render\n2 v2 #2\n3 v4 #3\n4 v4 render\n5 v4 #2\n"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "tauri", feature = "test-utils"))]
+    fn tauri_plugin_dispatches_invokes_and_emits_state() {
+        use std::sync::{Arc, Mutex};
+        use tauri::{test::*, Listener};
+
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let mut context = mock_context(noop_assets());
+        context.runtime_authority_mut().__allow_command(
+            String::from("plugin:universe|dispatch"),
+            tauri::utils::acl::ExecutionContext::Local,
+        );
+
+        let app = mock_builder()
+            .plugin(TauriUniversePlugin::new(universe.clone()))
+            .build(context)
+            .unwrap();
+
+        let payloads = Arc::new(Mutex::new(vec![]));
+        let payloads_clone = payloads.clone();
+        app.listen_any(UNIVERSE_CHANGED_EVENT, move |event| {
+            payloads_clone
+                .lock()
+                .unwrap()
+                .push(event.payload().to_string());
+        });
+
+        let webview = tauri::WebviewWindowBuilder::new(&app, "main", Default::default())
+            .build()
+            .unwrap();
+
+        let message = serde_json::to_value(Msg::Increment(2)).unwrap();
+        let response = get_ipc_response(
+            &webview,
+            tauri::webview::InvokeRequest {
+                cmd: String::from("plugin:universe|dispatch"),
+                callback: tauri::ipc::CallbackFn(0),
+                error: tauri::ipc::CallbackFn(1),
+                url: "tauri://localhost".parse().unwrap(),
+                body: serde_json::json!({ "message": message }).into(),
+                headers: Default::default(),
+                invoke_key: INVOKE_KEY.to_string(),
+            },
+        );
+
+        assert!(response.is_ok());
+        assert_eq!(universe.read().counter, 2);
+
+        let payloads = payloads.lock().unwrap();
+        let emitted: TestAppState = serde_json::from_str(&payloads[0]).unwrap();
+        assert_eq!(payloads.len(), 1);
+        assert_eq!(emitted.counter, 2);
+    }
+
+    #[test]
+    fn closed_universe_drops_messages() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.msg(Msg::Increment(1));
+        universe.close();
+        universe.msg(Msg::Increment(1));
+
+        assert!(universe.is_closed());
+        assert_eq!(universe.read().counter, 1);
+        assert_eq!(universe.version(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "futures")]
+    fn pump_stops_when_universe_is_closed() {
+        use futures::{channel::mpsc, StreamExt};
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let (sender, receiver) = mpsc::unbounded();
+
+        universe.subscribe_forever(|universe| {
+            if universe.read().counter == 2 {
+                universe.close();
+            }
+        });
+
+        for _ in 0..4 {
+            sender.unbounded_send(Msg::Increment(1)).unwrap();
+        }
+
+        drop(sender);
+
+        let mut receiver = receiver;
+        futures::executor::block_on(universe.pump(receiver.by_ref()));
+
+        assert_eq!(universe.read().counter, 2);
+        // Pumping stopped right after the message that closed the universe.
+        assert_eq!(futures::executor::block_on(receiver.count()), 2);
+    }
+
+    struct OrderState {
+        applied: Vec<&'static str>,
+    }
+
+    impl AppUniverseCore for OrderState {
+        type Message = &'static str;
+
+        fn msg(&mut self, message: Self::Message) {
+            self.applied.push(message);
+        }
+    }
+
+    fn dispatch_interleaved_priorities(universe: &mut AppUniverse<OrderState>) {
+        use std::cell::Cell;
+
+        let queued = Rc::new(Cell::new(false));
+        universe.subscribe_forever(move |universe| {
+            if queued.replace(true) {
+                return;
+            }
+
+            universe.msg_with_priority("low-1", DispatchPriority::Low);
+            universe.msg("normal-1");
+            universe.msg_with_priority("high-1", DispatchPriority::High);
+            universe.msg_with_priority("low-2", DispatchPriority::Low);
+            universe.msg_with_priority("high-2", DispatchPriority::High);
+            universe.msg_with_priority("normal-2", DispatchPriority::Normal);
+            universe.msg_with_priority("high-3", DispatchPriority::High);
+        });
+
+        universe.msg("start");
+    }
+
+    #[test]
+    fn priority_lanes_drain_high_before_normal_before_low() {
+        let mut universe = AppUniverse::new(OrderState { applied: vec![] });
+
+        dispatch_interleaved_priorities(&mut universe);
+
+        assert_eq!(
+            universe.read().applied,
+            vec!["start", "high-1", "high-2", "high-3", "normal-1", "normal-2", "low-1", "low-2"]
+        );
+    }
+
+    #[test]
+    fn starvation_limit_promotes_waiting_low_dispatches() {
+        let mut universe = AppUniverse::new(OrderState { applied: vec![] });
+        universe.set_starvation_limit(2);
+
+        dispatch_interleaved_priorities(&mut universe);
+
+        assert_eq!(
+            universe.read().applied,
+            vec!["start", "high-1", "high-2", "low-1", "high-3", "normal-1", "low-2", "normal-2"]
+        );
+    }
+
+    #[test]
+    fn same_universe_compares_handles() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let other_universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        assert!(universe.same_universe(&universe.clone()));
+        assert!(!universe.same_universe(&other_universe));
+    }
+
+    #[cfg(all(feature = "serde", feature = "async"))]
+    struct MemoryStorage {
+        saved: Rc<std::cell::RefCell<Vec<String>>>,
+        error: Option<StorageError>,
+    }
+
+    #[cfg(all(feature = "serde", feature = "async"))]
+    impl AsyncStorage for MemoryStorage {
+        fn save(&self, snapshot: String) -> StorageFuture<()> {
+            let saved = self.saved.clone();
+            let error = self.error.clone();
+
+            Box::pin(async move {
+                match error {
+                    Some(error) => Err(error),
+                    None => {
+                        saved.borrow_mut().push(snapshot);
+                        Ok(())
+                    }
+                }
+            })
+        }
+
+        fn load(&self) -> StorageFuture<Option<String>> {
+            let snapshot = self.saved.borrow().last().cloned();
+            Box::pin(async move { Ok(snapshot) })
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "async"))]
+    fn persist_async_collapses_pending_saves() {
+        use std::cell::RefCell;
+
+        let spawned = Rc::new(RefCell::new(vec![]));
+        let spawned_clone = spawned.clone();
+        let saved = Rc::new(RefCell::new(vec![]));
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.set_spawner(move |future| spawned_clone.borrow_mut().push(future));
+        let _ = universe
+            .persist_async(MemoryStorage {
+                saved: saved.clone(),
+                error: None,
+            })
+            .unwrap();
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+
+        let futures: Vec<LocalBoxFuture> = spawned.borrow_mut().drain(..).collect();
+        assert_eq!(futures.len(), 1);
+        for future in futures {
+            futures::executor::block_on(future);
+        }
+
+        assert_eq!(*saved.borrow(), vec![universe.dehydrate().unwrap()]);
+
+        let storage = MemoryStorage { saved, error: None };
+        let restored = futures::executor::block_on(AppUniverse::restore(
+            &storage,
+            TestAppState { counter: 0 },
+        ))
+        .unwrap();
+        assert_eq!(restored.read().counter, 3);
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "async"))]
+    fn persist_async_reports_storage_errors() {
+        use std::cell::RefCell;
+
+        let spawned = Rc::new(RefCell::new(vec![]));
+        let spawned_clone = spawned.clone();
+        let errors = Rc::new(RefCell::new(vec![]));
+        let errors_clone = errors.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.set_spawner(move |future| spawned_clone.borrow_mut().push(future));
+        universe.set_storage_error_handler(move |error| errors_clone.borrow_mut().push(error));
+        let _ = universe
+            .persist_async(MemoryStorage {
+                saved: Rc::new(RefCell::new(vec![])),
+                error: Some(StorageError::QuotaExceeded),
+            })
+            .unwrap();
+
+        universe.msg(Msg::Increment(1));
+
+        let futures: Vec<LocalBoxFuture> = spawned.borrow_mut().drain(..).collect();
+        for future in futures {
+            futures::executor::block_on(future);
+        }
+
+        assert_eq!(*errors.borrow(), vec![StorageError::QuotaExceeded]);
+    }
+
+    #[cfg(all(feature = "indexeddb-storage", target_arch = "wasm32"))]
+    mod indexeddb_storage {
+        use super::*;
+        use wasm_bindgen_test::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        // Records every snapshot written to the wrapped storage.
+        struct CountingStorage {
+            storage: IndexedDbStorage,
+            written: Rc<std::cell::RefCell<Vec<String>>>,
+        }
+
+        impl AsyncStorage for CountingStorage {
+            fn save(&self, snapshot: String) -> StorageFuture<()> {
+                self.written.borrow_mut().push(snapshot.clone());
+                self.storage.save(snapshot)
+            }
+
+            fn load(&self) -> StorageFuture<Option<String>> {
+                self.storage.load()
+            }
+        }
+
+        #[wasm_bindgen_test]
+        async fn first_open_loads_nothing() {
+            let storage = IndexedDbStorage::new("app-universe-first-open", "state", "universe");
+
+            assert_eq!(storage.load().await, Ok(None));
+        }
+
+        #[wasm_bindgen_test]
+        async fn save_and_load_round_trip() {
+            let storage = IndexedDbStorage::new("app-universe-round-trip", "state", "universe");
+            let universe = AppUniverse::new(TestAppState { counter: 4 });
+
+            storage.save(universe.dehydrate().unwrap()).await.unwrap();
+
+            let restored = AppUniverse::restore(&storage, TestAppState { counter: 0 })
+                .await
+                .unwrap();
+            assert_eq!(restored.read().counter, 4);
+        }
+
+        #[wasm_bindgen_test]
+        async fn storages_share_a_database_with_a_store_each() {
+            let drafts = IndexedDbStorage::new("app-universe-shared", "drafts", "universe");
+            let settings = IndexedDbStorage::new("app-universe-shared", "settings", "universe");
+
+            drafts.save(String::from("draft")).await.unwrap();
+            settings.save(String::from("dark")).await.unwrap();
+
+            assert_eq!(drafts.load().await, Ok(Some(String::from("draft"))));
+            assert_eq!(settings.load().await, Ok(Some(String::from("dark"))));
+        }
+
+        #[wasm_bindgen_test]
+        async fn concurrent_saves_collapse_to_the_newest_snapshot() {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let sender = Rc::new(std::cell::RefCell::new(Some(sender)));
+
+            let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+            universe.set_spawner(move |future: LocalBoxFuture| {
+                let sender = sender.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    future.await;
+                    if let Some(sender) = sender.borrow_mut().take() {
+                        let _ = sender.send(());
+                    }
+                })
+            });
+            let written = Rc::new(std::cell::RefCell::new(vec![]));
+            let _ = universe
+                .persist_async(CountingStorage {
+                    storage: IndexedDbStorage::new("app-universe-collapse", "state", "universe"),
+                    written: written.clone(),
+                })
+                .unwrap();
+
+            for _ in 0..5 {
+                universe.msg(Msg::Increment(1));
+            }
+            receiver.await.unwrap();
+
+            // The save only starts once the dispatches are done, so it writes the newest
+            // snapshot once instead of all five.
+            assert_eq!(*written.borrow(), vec![universe.dehydrate().unwrap()]);
+
+            let storage = IndexedDbStorage::new("app-universe-collapse", "state", "universe");
+            assert_eq!(
+                storage.load().await,
+                Ok(Some(universe.dehydrate().unwrap()))
+            );
+        }
+    }
+
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    mod wasm_dispatch {
+        use super::*;
+        use wasm_bindgen_test::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        async fn dispatch_async_spawns_locally_without_a_spawner() {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+            let sender = Rc::new(std::cell::RefCell::new(Some(sender)));
+
+            universe.subscribe_forever(move |_| {
+                if let Some(sender) = sender.borrow_mut().take() {
+                    let _ = sender.send(());
+                }
+            });
+            universe
+                .dispatch_async(async { Msg::Increment(3) })
+                .unwrap();
+
+            receiver.await.unwrap();
+            assert_eq!(universe.read().counter, 3);
+        }
+    }
+
+    #[cfg(all(feature = "worker-bridge", target_arch = "wasm32"))]
+    mod worker_bridge_in_browser {
+        use super::*;
+        use wasm_bindgen::JsValue;
+        use wasm_bindgen_test::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        // A worker that speaks the host protocol for `TestAppState`.
+        const HOST_SCRIPT: &str = r#"
+            let counter = 0;
+            let version = 0;
+            const postSnapshot = () =>
+                postMessage(JSON.stringify({ kind: "Snapshot", version, state: { counter } }));
+
+            postMessage(JSON.stringify({ kind: "Ready" }));
+            postSnapshot();
+            onmessage = (event) => {
+                counter += JSON.parse(event.data).Increment;
+                version += 1;
+                postSnapshot();
+            };
+        "#;
+
+        fn host_script_url() -> String {
+            let parts = js_sys::Array::of1(&JsValue::from_str(HOST_SCRIPT));
+            let options = web_sys::BlobPropertyBag::new();
+            options.set_type("application/javascript");
+            let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options).unwrap();
+            web_sys::Url::create_object_url_with_blob(&blob).unwrap()
+        }
+
+        #[wasm_bindgen_test]
+        async fn client_round_trips_through_a_worker() {
+            let (sender, receiver) = futures::channel::oneshot::channel();
+            let sender = Rc::new(std::cell::RefCell::new(Some(sender)));
+
+            let client = WorkerUniverseClient::<TestAppState>::spawn(&host_script_url()).unwrap();
+            let subscription = client.subscribe(Box::new(move |client| {
+                if client.version() == 2 {
+                    if let Some(sender) = sender.borrow_mut().take() {
+                        let _ = sender.send(());
+                    }
+                }
+            }));
+
+            // Sent before the worker is ready, so these are buffered and flushed in order.
+            client.msg(Msg::Increment(1)).unwrap();
+            client.msg(Msg::Increment(2)).unwrap();
+
+            receiver.await.unwrap();
+            assert!(client.is_ready());
+            assert_eq!(client.read().unwrap().counter, 3);
+            assert!(client.unsubscribe(subscription));
+        }
+    }
+
+    fn count_notifications(universe: &mut AppUniverse<TestAppState>) -> Rc<std::cell::Cell<u32>> {
+        let notifications = Rc::new(std::cell::Cell::new(0));
+        let notifications_clone = notifications.clone();
+
+        universe.subscribe_forever(move |_| {
+            notifications_clone.set(notifications_clone.get() + 1);
+        });
+
+        notifications
+    }
+
+    #[test]
+    fn immediate_strategy_notifies_on_every_dispatch() {
+        let mut universe = AppUniverse::builder(TestAppState { counter: 0 })
+            .notification_strategy(NotificationStrategy::Immediate)
+            .build();
+        let notifications = count_notifications(&mut universe);
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(0));
+
+        assert_eq!(notifications.get(), 2);
+    }
+
+    #[test]
+    fn on_change_strategy_skips_unchanged_state() {
+        #[derive(Clone, PartialEq)]
+        struct Flag(bool);
+
+        impl AppUniverseCore for Flag {
+            type Message = bool;
+
+            fn msg(&mut self, value: bool) {
+                self.0 = value;
+            }
+        }
+
+        let mut universe = AppUniverse::builder(Flag(false)).notify_on_change().build();
+        let notifications = Rc::new(std::cell::Cell::new(0));
+        let notifications_clone = notifications.clone();
+        universe.subscribe_forever(move |_| {
+            notifications_clone.set(notifications_clone.get() + 1);
+        });
+
+        universe.msg(false);
+        universe.msg(true);
+        universe.msg(true);
+        universe.msg(false);
+
+        assert_eq!(notifications.get(), 2);
+        assert_eq!(universe.version(), 4);
+    }
+
+    #[test]
+    fn manual_strategy_notifies_only_on_flush() {
+        let mut universe = AppUniverse::builder(TestAppState { counter: 0 })
+            .notification_strategy(NotificationStrategy::Manual)
+            .build();
+        let notifications = count_notifications(&mut universe);
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 0);
+        assert_eq!(universe.read().counter, 2);
+
+        universe.flush();
+        universe.flush();
+        assert_eq!(notifications.get(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn dispatch_json_dispatches_and_classifies_errors() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        assert!(universe.dispatch_json(r#"{"Increment": 5}"#).is_ok());
+        assert_eq!(universe.read().counter, 5);
+
+        match universe.dispatch_json(r#"{"Increment": 5"#) {
+            Err(DispatchJsonError::Malformed(_)) => {}
+            _ => panic!("Expected malformed JSON"),
+        }
+
+        match universe.dispatch_json(r#"{"Decrement": 5}"#) {
+            Err(error @ DispatchJsonError::NoMatchingMessage(_)) => {
+                assert!(error.to_string().contains("unknown variant `Decrement`"));
+            }
+            _ => panic!("Expected no matching message"),
+        }
+
+        assert_eq!(universe.version(), 1);
+    }
+
+    #[test]
+    fn try_unsubscribe_reports_whether_anything_was_removed() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let subscription = universe.subscribe(|_| {});
+
+        assert!(universe.try_unsubscribe(subscription.clone()));
+        assert!(!universe.try_unsubscribe(subscription));
+    }
+
+    #[test]
+    fn subscriber_ctx_unsubscribes_itself_after_a_condition() {
+        use std::cell::Cell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let seen = Rc::new(Cell::new(0));
+        let seen_clone = seen.clone();
+
+        let subscription = universe.subscribe_with_ctx(Box::new(move |ctx| {
+            if ctx.read().counter >= 2 {
+                ctx.unsubscribe_self();
+            }
+            seen_clone.set(seen_clone.get() + 1);
+        }));
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(seen.get(), 2);
+        assert_eq!(universe.read().counter, 3);
+        assert!(!universe.try_unsubscribe(subscription));
+    }
+
+    #[test]
+    fn subscriber_ctx_dispatches_follow_up_messages() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let _ = universe.subscribe_with_ctx(Box::new(move |ctx| {
+            let counter = ctx.read().counter;
+            seen_clone.borrow_mut().push(counter);
+
+            if counter == 1 {
+                ctx.dispatch(Msg::Increment(10));
+                assert_eq!(ctx.read().counter, 1);
+                assert!(ctx.universe().upgrade().is_some());
+            }
+        }));
+
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(*seen.borrow(), vec![1, 11]);
+    }
+
+    #[test]
+    fn weak_universe_does_not_keep_the_universe_alive() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let weak_universe = universe.downgrade();
+
+        assert!(weak_universe.upgrade().unwrap().same_universe(&universe));
+
+        drop(universe);
+        assert!(weak_universe.upgrade().is_none());
+    }
+
+    #[test]
+    fn subscribe_weak_self_dispatches_without_leaking() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let weak_universe = universe.downgrade();
+
+        let _ = universe.subscribe_weak_self(Box::new(|weak_universe| {
+            let universe = weak_universe.upgrade().unwrap();
+            if universe.read().counter < 3 {
+                universe.msg(Msg::Increment(1));
+            }
+        }));
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(universe.read().counter, 3);
+
+        drop(universe);
+        assert!(weak_universe.upgrade().is_none());
+    }
+
+    #[test]
+    fn read_cow_borrows_until_ownership_is_needed() {
+        #[derive(Clone)]
+        struct Title(&'static str);
+
+        impl AppUniverseCore for Title {
+            type Message = &'static str;
+
+            fn msg(&mut self, title: Self::Message) {
+                self.0 = title;
+            }
+        }
+
+        let universe = AppUniverse::new(Title("draft"));
+
+        let mut title = universe.read_cow();
+        assert!(title.is_borrowed());
+        assert_eq!(title.0, "draft");
+
+        title.to_mut().0 = "edited";
+        assert!(!title.is_borrowed());
+
+        universe.msg("published");
+        assert_eq!(title.0, "edited");
+        assert_eq!(universe.read_cow().into_owned().0, "published");
+    }
+
+    #[test]
+    fn dispatch_coalesced_applies_the_last_message_per_key() {
+        use std::cell::RefCell;
+        use std::time::Duration;
+
+        type Task = Box<dyn FnOnce()>;
+
+        let tasks: Rc<RefCell<Vec<Task>>> = Rc::new(RefCell::new(vec![]));
+        let scheduled = tasks.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+        universe.set_coalescing_window(
+            Duration::from_millis(16),
+            move |delay: Duration, task: Task| {
+                assert_eq!(delay, Duration::from_millis(16));
+                scheduled.borrow_mut().push(task);
+            },
+        );
+
+        for progress in [10, 20, 30] {
+            universe
+                .dispatch_coalesced("download", Msg::Increment(progress))
+                .unwrap();
+        }
+
+        assert_eq!(universe.read().counter, 0);
+        assert_eq!(tasks.borrow().len(), 1);
+
+        let task = tasks.borrow_mut().pop().unwrap();
+        task();
+
+        assert_eq!(universe.read().counter, 30);
+        assert_eq!(universe.version(), 1);
+        assert_eq!(notifications.get(), 1);
+    }
+
+    #[test]
+    fn frozen_universe_rejects_dispatches() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        universe.freeze();
+        assert!(universe.is_frozen());
+        assert_eq!(
+            universe.try_msg(Msg::Increment(1)),
+            Err(UniverseError::Frozen)
+        );
+        assert_eq!(universe.read().counter, 0);
+        assert_eq!(universe.version(), 0);
+        assert_eq!(notifications.get(), 0);
+
+        universe.thaw();
+        assert_eq!(universe.try_msg(Msg::Increment(1)), Ok(()));
+        assert_eq!(universe.read().counter, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dispatched while the universe is frozen")]
+    #[cfg(debug_assertions)]
+    fn msg_panics_while_frozen_in_debug_builds() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        universe.freeze();
+        universe.msg(Msg::Increment(1));
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn subscriptions_info_describes_registered_subscriptions() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let render = universe.subscribe_labeled("render", |_| {});
+        universe.msg(Msg::Increment(1));
+        let first = universe.subscribe_with_priority(|_| {}, SubscriberPriority::First);
+
+        let info = universe.subscriptions_info();
+
+        assert_eq!(info.len(), 2);
+        assert_eq!(info[0].id, first.id());
+        assert_eq!(info[0].label, None);
+        assert_eq!(info[0].priority, SubscriberPriority::First);
+        assert_eq!(info[0].created_version, 1);
+        assert_eq!(info[1].id, render.id());
+        assert_eq!(info[1].label.as_deref(), Some("render"));
+        assert_eq!(info[1].priority, SubscriberPriority::default());
+        assert_eq!(info[1].created_version, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn derived_universe_follows_its_source() {
+        use std::cell::RefCell;
+
+        let universe = AppUniverse::new(TestAppState { counter: 1 });
+        let mut doubled = universe.derived(|state: &TestAppState| state.counter * 2);
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let subscription = doubled.subscribe(move |value| seen_clone.borrow_mut().push(*value));
+        assert_eq!(*doubled.read(), 2);
+
+        universe.msg(Msg::Increment(2));
+        assert_eq!(*doubled.read(), 6);
+
+        assert!(doubled.unsubscribe(subscription));
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*doubled.read(), 8);
+        assert_eq!(*seen.borrow(), vec![6]);
+
+        assert_eq!(universe.read_subscriptions().len(), 1);
+        drop(doubled);
+        assert_eq!(universe.read_subscriptions().len(), 0);
+    }
+
+    #[test]
+    fn derived_subscribers_can_unsubscribe_and_subscribe_while_notified() {
+        use std::cell::{Cell, RefCell};
+
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let doubled = Rc::new(RefCell::new(
+            universe.derived(|state: &TestAppState| state.counter * 2),
+        ));
+        let seen = Rc::new(RefCell::new(vec![]));
+
+        // The first subscriber replaces itself with one that records every later value.
+        let first = Rc::new(Cell::new(None));
+        let (weak_doubled, first_clone, seen_clone) =
+            (Rc::downgrade(&doubled), first.clone(), seen.clone());
+        let id = doubled.borrow_mut().subscribe(move |value| {
+            let doubled = weak_doubled.upgrade().unwrap();
+            let mut doubled = doubled.borrow_mut();
+            assert!(doubled.unsubscribe(first_clone.get().unwrap()));
+
+            let seen = seen_clone.clone();
+            doubled.subscribe(move |value| seen.borrow_mut().push(*value));
+            seen_clone.borrow_mut().push(*value);
+        });
+        first.set(Some(id));
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*seen.borrow(), vec![2]);
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*seen.borrow(), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn read2_borrows_two_fields_at_once() {
+        struct Profile {
+            name: String,
+            tags: Vec<&'static str>,
+        }
+
+        impl AppUniverseCore for Profile {
+            type Message = &'static str;
+
+            fn msg(&mut self, tag: Self::Message) {
+                self.tags.push(tag);
+            }
+        }
+
+        let universe = AppUniverse::new(Profile {
+            name: String::from("Ada"),
+            tags: vec![],
+        });
+        universe.msg("admin");
+
+        let (name, tags) = universe.read2(|profile| profile.name.as_str(), |profile| &profile.tags);
+        let first_tag = universe.split_read(|profile| &profile.tags[0]);
+
+        assert_eq!(&*name, "Ada");
+        assert_eq!(*tags, vec!["admin"]);
+        assert_eq!(*first_tag, "admin");
+    }
+
+    #[test]
+    #[cfg(feature = "debug-invariant")]
+    fn invariant_check_reports_changes_outside_of_msg() {
+        use std::cell::{Cell, RefCell};
+        use std::hash::{Hash, Hasher};
+
+        struct Shared(Rc<Cell<u8>>);
+
+        impl Hash for Shared {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.get().hash(state);
+            }
+        }
+
+        impl AppUniverseCore for Shared {
+            type Message = u8;
+
+            fn msg(&mut self, value: Self::Message) {
+                self.0.set(value);
+            }
+        }
+
+        let cell = Rc::new(Cell::new(0));
+        let universe = AppUniverse::new(Shared(cell.clone()));
+        let violations = Rc::new(RefCell::new(vec![]));
+        let violations_clone = violations.clone();
+        universe.enable_invariant_check_with(move |version| {
+            violations_clone.borrow_mut().push(version)
+        });
+
+        universe.msg(1);
+        assert_eq!(universe.read().0.get(), 1);
+        assert!(violations.borrow().is_empty());
+
+        cell.set(5);
+        assert_eq!(universe.read().0.get(), 5);
+        assert_eq!(universe.read().0.get(), 5);
+        assert_eq!(*violations.borrow(), vec![1]);
+    }
+
+    #[test]
+    fn into_inner_requires_the_last_handle() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let clone = universe.clone();
+        universe.msg(Msg::Increment(4));
+
+        let universe = match universe.into_inner() {
+            Ok(_) => panic!("A clone of the universe still exists"),
+            Err(universe) => universe,
+        };
+        assert!(universe.same_universe(&clone));
+
+        drop(clone);
+        let weak_universe = universe.downgrade();
+        let state = universe.into_inner().ok().unwrap();
+
+        assert_eq!(state.counter, 4);
+        assert!(weak_universe.upgrade().is_none());
+    }
+
+    #[test]
+    fn transaction_commits_once_or_rolls_back() {
+        #[derive(Clone)]
+        struct Account {
+            balance: i32,
+        }
+
+        impl AppUniverseCore for Account {
+            type Message = i32;
+
+            fn msg(&mut self, amount: Self::Message) {
+                self.balance += amount;
+            }
+        }
+
+        let mut universe = AppUniverse::new(Account { balance: 10 });
+        let notifications = Rc::new(std::cell::Cell::new(0));
+        let notifications_clone = notifications.clone();
+        universe.subscribe_forever(move |_| notifications_clone.set(notifications_clone.get() + 1));
+
+        let failed = universe.transaction(|universe| {
+            universe.msg(-5);
+            universe.msg(-10);
+            if universe.read().balance < 0 {
+                return Err("Insufficient funds");
+            }
+            Ok(())
+        });
+
+        assert_eq!(failed, Err("Insufficient funds"));
+        assert_eq!(universe.read().balance, 10);
+        assert_eq!(universe.version(), 2);
+        assert_eq!(notifications.get(), 0);
+
+        let committed = universe.transaction(|universe| {
+            universe.msg(-5);
+            universe.msg(3);
+            Ok::<_, &str>(())
+        });
+
+        assert_eq!(committed, Ok(()));
+        assert_eq!(universe.read().balance, 8);
+        assert_eq!(universe.version(), 4);
+        assert_eq!(notifications.get(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "transaction can't be called while a dispatch is being applied")]
+    fn transaction_panics_inside_a_subscriber() {
+        #[derive(Clone)]
+        struct Total(u32);
+
+        impl AppUniverseCore for Total {
+            type Message = u32;
+
+            fn msg(&mut self, amount: Self::Message) {
+                self.0 += amount;
+            }
+        }
+
+        let mut universe = AppUniverse::new(Total(0));
+        universe.subscribe_forever(|universe| {
+            let _ = universe.transaction(|universe| {
+                universe.msg(1);
+                Err(())
+            });
+        });
+
+        universe.msg(1);
+    }
+
+    #[test]
+    fn msg_with_dispatches_follow_ups_after_the_current_message() {
+        struct Checkout {
+            log: Vec<&'static str>,
+        }
+
+        enum CheckoutMsg {
+            Pay,
+            SendReceipt,
+        }
+
+        impl AppUniverseCore for Checkout {
+            type Message = CheckoutMsg;
+
+            fn msg(&mut self, message: Self::Message) {
+                self.msg_with(message, &Dispatcher::new());
+            }
+
+            fn msg_with(&mut self, message: Self::Message, dispatcher: &Dispatcher<Self>) {
+                match message {
+                    CheckoutMsg::Pay => {
+                        dispatcher.dispatch(CheckoutMsg::SendReceipt);
+                        self.log.push("paid");
+                    }
+                    CheckoutMsg::SendReceipt => self.log.push("receipt sent"),
+                }
+            }
+        }
+
+        let mut universe = AppUniverse::new(Checkout { log: vec![] });
+        let notifications = Rc::new(std::cell::RefCell::new(vec![]));
+        let notifications_clone = notifications.clone();
+        universe.subscribe_forever(move |universe| {
+            notifications_clone
+                .borrow_mut()
+                .push(universe.read().log.len())
+        });
+
+        universe.msg(CheckoutMsg::Pay);
+
+        assert_eq!(universe.read().log, vec!["paid", "receipt sent"]);
+        assert_eq!(*notifications.borrow(), vec![1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn subscriber_metrics_time_every_invocation() {
+        use std::time::Duration;
+
+        let clock = ManualClock::new();
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.set_clock(clock.clone());
+
+        let slow_clock = clock.clone();
+        let _slow = universe.subscribe_labeled("slow", move |_| {
+            slow_clock.advance(Duration::from_millis(5))
+        });
+        let _fast = universe.subscribe(|_| {});
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+
+        let metrics = universe.subscriber_metrics();
+        assert_eq!(
+            metrics[0],
+            (String::from("slow"), 2, Duration::from_millis(10))
+        );
+        assert!(metrics[1].0.starts_with('#'));
+        assert_eq!((metrics[1].1, metrics[1].2), (2, Duration::ZERO));
+    }
+
+    #[test]
+    fn adapted_core_receives_forwarded_messages() {
+        struct CounterCore {
+            value: u32,
+        }
+
+        impl AppUniverseCore for CounterCore {
+            type Message = u32;
+
+            fn msg(&mut self, amount: Self::Message) {
+                self.value += amount;
+            }
+        }
+
+        enum ParentMsg {
+            Child(u32),
+            Reset,
+        }
+
+        struct Parent {
+            resets: u32,
+            child: Adapted<CounterCore, ParentMsg>,
+        }
+
+        impl AppUniverseCore for Parent {
+            type Message = ParentMsg;
+
+            fn msg(&mut self, message: Self::Message) {
+                if let Err(ParentMsg::Reset) = self.child.forward(message) {
+                    self.resets += 1;
+                }
+            }
+        }
+
+        let universe = AppUniverse::new(Parent {
+            resets: 0,
+            child: adapt(CounterCore { value: 0 }, |message| match message {
+                ParentMsg::Child(amount) => Ok(amount),
+                other => Err(other),
+            }),
+        });
+
+        universe.msg(ParentMsg::Child(2));
+        universe.msg(ParentMsg::Reset);
+        universe.msg(ParentMsg::Child(3));
+
+        assert_eq!(universe.read().child.value, 5);
+        assert_eq!(universe.read().resets, 1);
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn captured_messages_can_be_applied_afterwards() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        universe.start_capturing();
+        universe.msg(Msg::Increment(2));
+        universe.msg_batch(vec![Msg::Increment(3), Msg::Increment(4)]);
+
+        assert_eq!(universe.captured_messages().len(), 3);
+        assert!(matches!(universe.captured_messages()[1], Msg::Increment(3)));
+        assert_eq!(universe.read().counter, 0);
+        assert_eq!(notifications.get(), 0);
+
+        universe.drain_captured_and_apply();
+
+        assert!(universe.captured_messages().is_empty());
+        assert_eq!(universe.read().counter, 9);
+        assert_eq!(universe.version(), 3);
+        assert_eq!(notifications.get(), 3);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(universe.read().counter, 10);
+    }
+
+    #[test]
+    fn subscribe_map_passes_the_mapped_value() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let labels = Rc::new(RefCell::new(vec![]));
+        let labels_clone = labels.clone();
+
+        let _subscription = universe.subscribe_map(
+            |state: &TestAppState| format!("{} items", state.counter / 2),
+            move |label| labels_clone.borrow_mut().push(label),
+        );
+
+        universe.msg(Msg::Increment(2));
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(*labels.borrow(), vec!["1 items", "1 items"]);
+    }
+
+    #[test]
+    fn subscribing_from_a_subscriber_takes_effect_after_the_notification() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+        let registered = Rc::new(std::cell::Cell::new(false));
+
+        universe.subscribe_forever(move |mut universe| {
+            if registered.replace(true) {
+                return;
+            }
+
+            let seen = seen_clone.clone();
+            universe
+                .subscribe_forever(move |universe| seen.borrow_mut().push(universe.read().counter));
+        });
+
+        universe.msg(Msg::Increment(1));
+        assert!(seen.borrow().is_empty());
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*seen.borrow(), vec![2, 3]);
+    }
+
+    #[test]
+    fn unsubscribing_from_a_subscriber_skips_the_rest_of_the_pass() {
+        use std::cell::{Cell, RefCell};
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let own_subscription = Rc::new(RefCell::new(None));
+        let own_subscription_clone = own_subscription.clone();
+        let later_guard: Rc<RefCell<Option<SubscriptionGuard<TestAppState>>>> =
+            Rc::new(RefCell::new(None));
+        let later_guard_clone = later_guard.clone();
+        let later_runs = Rc::new(Cell::new(0));
+        let later_runs_clone = later_runs.clone();
+
+        let subscription = universe.subscribe(move |mut universe| {
+            drop(later_guard_clone.borrow_mut().take());
+            if let Some(subscription) = own_subscription_clone.borrow_mut().take() {
+                assert!(universe.try_unsubscribe(subscription));
+            }
+        });
+        *own_subscription.borrow_mut() = Some(subscription);
+        *later_guard.borrow_mut() = Some(universe.subscribe_guarded(move |_| {
+            later_runs_clone.set(later_runs_clone.get() + 1);
+        }));
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(later_runs.get(), 0);
+        assert_eq!(universe.subscriber_count(), 0);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(later_runs.get(), 0);
+    }
+
+    #[test]
+    fn with_mut_bumps_the_version_and_notifies() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 1 });
+        let notifications = count_notifications(&mut universe);
+
+        let previous = universe.with_mut(|state| std::mem::replace(&mut state.counter, 7));
+
+        assert_eq!(previous, 1);
+        assert_eq!(universe.read().counter, 7);
+        assert_eq!(universe.version(), 1);
+        assert_eq!(notifications.get(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "with_mut can't be called on a frozen universe")]
+    fn with_mut_panics_on_a_frozen_universe() {
+        let universe = AppUniverse::new(TestAppState { counter: 1 });
+        universe.freeze();
+
+        universe.with_mut(|state| state.counter = 7);
+    }
+
+    #[test]
+    fn subscribe_batch_reports_the_message_count() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let batches = Rc::new(RefCell::new(vec![]));
+        let batches_clone = batches.clone();
+
+        let _subscription = universe.subscribe_batch(Box::new(move |batch, _| {
+            batches_clone.borrow_mut().push(batch);
+        }));
+
+        universe.msg_batch(vec![
+            Msg::Increment(1),
+            Msg::Increment(2),
+            Msg::Increment(3),
+        ]);
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(
+            *batches.borrow(),
+            vec![
+                BatchInfo {
+                    message_count: 3,
+                    start_version: 0,
+                    end_version: 3,
+                },
+                BatchInfo {
+                    message_count: 1,
+                    start_version: 3,
+                    end_version: 4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn poll_changed_only_clones_new_versions() {
+        #[derive(Clone)]
+        struct Frame(u32);
+
+        impl AppUniverseCore for Frame {
+            type Message = u32;
+
+            fn msg(&mut self, value: Self::Message) {
+                self.0 = value;
+            }
+        }
+
+        let universe = AppUniverse::new(Frame(0));
+        universe.msg(5);
+
+        let (last_version, state) = universe.poll_changed(0).unwrap();
+        assert_eq!(last_version, 1);
+        assert_eq!(state.0, 5);
+
+        assert!(universe.poll_changed(last_version).is_none());
+
+        universe.msg(8);
+        let (last_version, state) = universe.poll_changed(last_version).unwrap();
+        assert_eq!(last_version, 2);
+        assert_eq!(state.0, 8);
+    }
+
+    #[test]
+    fn shared_state_bypasses_notifications() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        let state = universe.shared_state();
+        state.borrow_mut().counter = 4;
+
+        assert_eq!(universe.read().counter, 4);
+        assert_eq!(universe.version(), 0);
+        assert_eq!(notifications.get(), 0);
+    }
+
+    #[test]
+    fn notify_selective_only_runs_matching_subscribers() {
+        use std::cell::Cell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let unlabeled = count_notifications(&mut universe);
+        let labeled = Rc::new(Cell::new(0));
+        let labeled_clone = labeled.clone();
+
+        let _subscription = universe.subscribe_labeled("renderer", move |_| {
+            labeled_clone.set(labeled_clone.get() + 1)
+        });
+
+        universe.notify_selective(|meta| meta.label.is_some());
+
+        assert_eq!(labeled.get(), 1);
+        assert_eq!(unlabeled.get(), 0);
+        assert_eq!(universe.version(), 0);
+    }
+
+    #[cfg(feature = "sycamore")]
+    #[test]
+    fn sycamore_signal_follows_the_universe_until_the_scope_is_disposed() {
+        use sycamore::reactive::create_scope_immediate;
+
+        #[derive(Clone)]
+        struct Counter(u32);
+
+        impl AppUniverseCore for Counter {
+            type Message = u32;
+
+            fn msg(&mut self, value: Self::Message) {
+                self.0 = value;
+            }
+        }
+
+        let universe = AppUniverse::new(Counter(0));
+
+        create_scope_immediate(|cx| {
+            let signal = universe.to_sycamore_signal(cx);
+            universe.msg(4);
+            assert_eq!(signal.get().0, 4);
+            assert_eq!(universe.subscriber_count(), 1);
+        });
+
+        assert_eq!(universe.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn messages_since_returns_the_recorded_messages_after_a_version() {
+        struct Total(u32);
+
+        impl AppUniverseCore for Total {
+            type Message = u32;
+
+            fn msg(&mut self, amount: Self::Message) {
+                self.0 += amount;
+            }
+        }
+
+        let universe = AppUniverse::new(Total(0));
+        universe.msg(100);
+
+        universe.record_messages();
+        universe.msg(1);
+        universe.msg_batch(vec![2, 3]);
+        universe.msg(4);
+
+        assert_eq!(universe.messages_since(3), vec![3, 4]);
+        assert_eq!(universe.messages_since(1), vec![1, 2, 3, 4]);
+        assert!(universe.messages_since(5).is_empty());
+    }
+
+    #[test]
+    fn recover_by_replay_rebuilds_the_state_after_a_panic() {
+        #[derive(Clone)]
+        struct Total(u32);
+
+        #[derive(Clone)]
+        enum TotalMsg {
+            Add(u32),
+            Corrupt,
+        }
+
+        impl AppUniverseCore for Total {
+            type Message = TotalMsg;
+
+            fn msg(&mut self, message: Self::Message) {
+                match message {
+                    TotalMsg::Add(amount) => self.0 += amount,
+                    TotalMsg::Corrupt => {
+                        self.0 = 999;
+                        panic!("corrupted the total");
+                    }
+                }
+            }
+        }
+
+        let universe = AppUniverse::new(Total(1));
+        universe.enable_replay_recovery();
+        universe.msg(TotalMsg::Add(2));
+        universe.msg(TotalMsg::Add(3));
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            universe.msg_batch(vec![TotalMsg::Add(4), TotalMsg::Corrupt]);
+        }));
+        assert!(result.is_err());
+        assert_eq!(universe.read().0, 999);
+
+        universe.recover_by_replay().unwrap();
+
+        assert_eq!(universe.read().0, 6);
+        assert_eq!(universe.version(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn subscribe_async_spawns_a_future_per_notification() {
+        use std::cell::RefCell;
+
+        let spawned: Rc<RefCell<Vec<LocalBoxFuture>>> = Rc::new(RefCell::new(vec![]));
+        let spawned_clone = spawned.clone();
+
+        let persisted = Rc::new(RefCell::new(vec![]));
+        let persisted_clone = persisted.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.set_spawner(move |future| spawned_clone.borrow_mut().push(future));
+
+        let _subscription = universe
+            .subscribe_async(Box::new(move |universe| {
+                let counter = universe.read().counter;
+                let persisted = persisted_clone.clone();
+                Box::pin(async move { persisted.borrow_mut().push(counter) })
+            }))
+            .unwrap();
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(2));
+        assert!(persisted.borrow().is_empty());
+
+        let futures: Vec<LocalBoxFuture> = spawned.borrow_mut().drain(..).collect();
+        for future in futures.into_iter().rev() {
+            futures::executor::block_on(future);
+        }
+
+        assert_eq!(*persisted.borrow(), vec![3, 1]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn subscribe_json_patch_reports_the_changed_field() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 1 });
+        let patches = Rc::new(RefCell::new(vec![]));
+        let patches_clone = patches.clone();
+
+        let _subscription = universe.subscribe_json_patch(Box::new(move |ops| {
+            patches_clone.borrow_mut().push(ops);
+        }));
+
+        universe.msg(Msg::Increment(2));
+        universe.msg(Msg::Increment(0));
+
+        assert_eq!(
+            *patches.borrow(),
+            vec![vec![PatchOp::Replace {
+                path: String::from("/counter"),
+                value: serde_json::json!(3),
+            }]]
+        );
+    }
+
+    #[test]
+    fn pure_cores_replace_the_state() {
+        struct PureCounter {
+            value: u32,
+        }
+
+        impl PureUniverseCore for PureCounter {
+            type Message = u32;
+
+            fn reduce(&self, amount: Self::Message) -> Self {
+                PureCounter {
+                    value: self.value + amount,
+                }
+            }
+        }
+
+        let mut universe = AppUniverse::new(PureCounter { value: 1 });
+        let values = Rc::new(std::cell::RefCell::new(vec![]));
+        let values_clone = values.clone();
+
+        universe.subscribe_forever(move |universe| {
+            values_clone.borrow_mut().push(universe.read().value);
+        });
+
+        universe.msg(2);
+        universe.msg_batch(vec![3, 4]);
+
+        assert_eq!(*values.borrow(), vec![3, 10]);
+        assert_eq!(universe.version(), 3);
+    }
+
+    #[test]
+    fn subscribe_every_runs_on_every_nth_change() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let _subscription = universe.subscribe_every(3, move |universe| {
+            seen_clone.borrow_mut().push(universe.read().counter)
+        });
+
+        for _ in 0..7 {
+            universe.msg(Msg::Increment(1));
+        }
+
+        assert_eq!(*seen.borrow(), vec![3, 6]);
+    }
+
+    #[test]
+    fn restore_brings_back_a_snapshot() {
+        #[derive(Clone)]
+        struct Document(Vec<&'static str>);
+
+        impl AppUniverseCore for Document {
+            type Message = &'static str;
+
+            fn msg(&mut self, line: Self::Message) {
+                self.0.push(line);
+            }
+        }
+
+        let mut universe = AppUniverse::new(Document(vec!["title"]));
+        let before_import = universe.snapshot();
+
+        universe.msg_batch(vec!["imported", "also imported"]);
+        let after_import = universe.snapshot();
+
+        let notifications = Rc::new(std::cell::Cell::new(0));
+        let notifications_clone = notifications.clone();
+        universe.subscribe_forever(move |_| notifications_clone.set(notifications_clone.get() + 1));
+
+        universe.restore_snapshot(&before_import);
+        assert_eq!(universe.read().0, vec!["title"]);
+        assert_eq!(universe.version(), 3);
+        assert_eq!(notifications.get(), 1);
+
+        universe.restore_snapshot(&after_import);
+        assert_eq!(
+            universe.read().0,
+            vec!["title", "imported", "also imported"]
+        );
+        assert_eq!(before_import.state().0, vec!["title"]);
+    }
+
+    #[test]
+    fn approx_eq_selectors_ignore_sub_epsilon_changes() {
+        use std::cell::RefCell;
+
+        struct Dashboard {
+            average: f64,
+        }
+
+        impl AppUniverseCore for Dashboard {
+            type Message = f64;
+
+            fn msg(&mut self, delta: Self::Message) {
+                self.average += delta;
+            }
+        }
+
+        let mut universe = AppUniverse::new(Dashboard { average: 1.0 });
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let _subscription = universe.subscribe_selector(
+            |state| state.average,
+            approx_eq(),
+            move |average| seen_clone.borrow_mut().push(*average),
+        );
+
+        universe.msg(1e-12);
+        assert!(seen.borrow().is_empty());
+
+        universe.msg(0.1);
+        assert_eq!(seen.borrow().len(), 1);
+        assert!((seen.borrow()[0] - 1.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn observer_cores_receive_every_message() {
+        #[derive(Clone)]
+        enum Event {
+            Added(u32),
+            Cleared,
+        }
+
+        struct Sum(u32);
+        struct Count(u32);
+        struct History(Vec<u32>);
+
+        impl AppUniverseCore for Sum {
+            type Message = Event;
+
+            fn msg(&mut self, event: Self::Message) {
+                match event {
+                    Event::Added(value) => self.0 += value,
+                    Event::Cleared => self.0 = 0,
+                }
+            }
+        }
+
+        impl AppUniverseCore for Count {
+            type Message = Event;
+
+            fn msg(&mut self, event: Self::Message) {
+                if let Event::Added(_) = event {
+                    self.0 += 1;
+                }
+            }
+        }
+
+        impl AppUniverseCore for History {
+            type Message = Event;
+
+            fn msg(&mut self, event: Self::Message) {
+                if let Event::Added(value) = event {
+                    self.0.push(value);
+                }
+            }
+        }
+
+        let universe = AppUniverse::new(Sum(0));
+        let count = universe.register_observer_core(Count(0));
+        let history = universe.register_observer_core(History(vec![]));
+
+        universe.msg(Event::Added(2));
+        universe.msg_batch(vec![Event::Added(3), Event::Cleared]);
+
+        assert_eq!(universe.read().0, 0);
+        assert_eq!(count.read().0, 2);
+        assert_eq!(history.read().0, vec![2, 3]);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn with_mocked_state_notifies_and_restores_the_original() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 1 });
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        universe.subscribe_forever(move |universe| {
+            seen_clone.borrow_mut().push(universe.read().counter);
+        });
+
+        universe.with_mocked_state(TestAppState { counter: 200 }, |universe| {
+            assert_eq!(universe.read().counter, 200);
+        });
+
+        assert_eq!(*seen.borrow(), vec![200]);
+        assert_eq!(universe.read().counter, 1);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            universe
+                .with_mocked_state(TestAppState { counter: 50 }, |_| panic!("failed assertion"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(universe.read().counter, 1);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn with_mocked_state_rejects_dispatches() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 1 });
+        universe.subscribe_forever(|universe| {
+            if universe.read().counter > 100 {
+                universe.msg(Msg::Increment(1));
+            }
+        });
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            universe.with_mocked_state(TestAppState { counter: 200 }, |_| {});
+        }));
+        assert!(result.is_err());
+
+        universe.with_mocked_state(TestAppState { counter: 50 }, |universe| {
+            assert_eq!(
+                universe.try_msg(Msg::Increment(1)),
+                Err(UniverseError::Frozen)
+            );
+        });
+
+        assert!(!universe.is_frozen());
+        assert_eq!(universe.read().counter, 1);
+        assert_eq!(universe.version(), 0);
+    }
+
+    #[test]
+    fn background_messages_wait_for_the_next_interactive_one() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        universe.msg_with_notification_priority(Msg::Increment(1), MessagePriority::Background);
+        universe.msg_with_notification_priority(Msg::Increment(1), MessagePriority::Background);
+        assert_eq!(universe.read().counter, 2);
+        assert_eq!(notifications.get(), 0);
+
+        universe.msg_with_notification_priority(Msg::Increment(1), MessagePriority::Interactive);
+        assert_eq!(notifications.get(), 1);
+
+        universe.flush();
+        assert_eq!(notifications.get(), 1);
+
+        universe.msg_with_notification_priority(Msg::Increment(1), MessagePriority::Background);
+        universe.flush();
+        assert_eq!(notifications.get(), 2);
+    }
+
+    #[test]
+    fn countable_cores_expose_len_on_the_universe() {
+        struct Cart {
+            items: Vec<&'static str>,
+        }
+
+        impl AppUniverseCore for Cart {
+            type Message = &'static str;
+
+            fn msg(&mut self, item: Self::Message) {
+                self.items.push(item);
+            }
+        }
+
+        impl Countable for Cart {
+            fn len(&self) -> usize {
+                self.items.len()
+            }
+        }
+
+        let universe = AppUniverse::new(Cart { items: vec![] });
+        assert!(universe.is_empty());
+
+        universe.msg_batch(vec!["apples", "pears"]);
+        assert_eq!(universe.len(), 2);
+        assert!(!universe.is_empty());
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[derive(Clone, PartialEq, Debug)]
+    struct Ticks(u32);
+
+    #[cfg(feature = "test-utils")]
+    impl AppUniverseCore for Ticks {
+        type Message = bool;
+
+        fn msg(&mut self, use_clock: Self::Message) {
+            use std::sync::atomic::{AtomicU32, Ordering};
+
+            static CLOCK: AtomicU32 = AtomicU32::new(0);
+
+            if use_clock {
+                self.0 = CLOCK.fetch_add(1, Ordering::Relaxed);
+            } else {
+                self.0 += 1;
+            }
+        }
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn deterministic_replays_pass_the_check() {
+        let universe = AppUniverse::new(Ticks(0));
+        universe.enable_replay_recovery();
+        universe.msg_batch(vec![false, false, false]);
+
+        universe.assert_replay_deterministic();
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    #[should_panic(expected = "Replay is not deterministic")]
+    fn non_deterministic_replays_fail_the_check() {
+        let universe = AppUniverse::new(Ticks(0));
+        universe.enable_replay_recovery();
+        universe.msg_batch(vec![false, true]);
+
+        universe.assert_replay_deterministic();
+    }
+
+    #[test]
+    fn scheduled_subscribers_coalesce_notifications_until_the_flush() {
+        use std::cell::RefCell;
+
+        type Task = Box<dyn FnOnce()>;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let immediate = Rc::new(RefCell::new(vec![]));
+        let immediate_clone = immediate.clone();
+        let _immediate = universe.subscribe_scheduled(
+            ImmediateScheduler,
+            Box::new(move |universe| immediate_clone.borrow_mut().push(universe.read().counter)),
+        );
+
+        let ticks: Rc<RefCell<Vec<Task>>> = Rc::new(RefCell::new(vec![]));
+        let ticks_clone = ticks.clone();
+        let framed = Rc::new(RefCell::new(vec![]));
+        let framed_clone = framed.clone();
+        let _framed = universe.subscribe_scheduled(
+            move |task| ticks_clone.borrow_mut().push(task),
+            Box::new(move |universe| framed_clone.borrow_mut().push(universe.read().counter)),
+        );
+
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(2));
+        assert_eq!(*immediate.borrow(), vec![1, 3]);
+        assert_eq!(ticks.borrow().len(), 1);
+
+        let tasks: Vec<Task> = ticks.borrow_mut().drain(..).collect();
+        for task in tasks {
+            task();
+        }
+        assert_eq!(*framed.borrow(), vec![3]);
+    }
+
+    #[test]
+    fn read_or_falls_back_while_the_state_is_borrowed_mutably() {
+        let universe = AppUniverse::new(TestAppState { counter: 3 });
+        assert_eq!(universe.read_or(0, |state| state.counter), 3);
+
+        let state = universe.shared_state();
+        let _applying = state.borrow_mut();
+
+        assert_eq!(universe.read_or(0, |state| state.counter), 0);
+    }
+
+    #[test]
+    fn canceled_debounce_is_never_applied() {
+        use std::cell::{Cell, RefCell};
+        use std::time::Duration;
+
+        type Timer = (Duration, Box<dyn FnOnce()>);
+
+        // The fake time the timers are due at.
+        let now = Rc::new(Cell::new(Duration::ZERO));
+        let timers: Rc<RefCell<Vec<Timer>>> = Rc::new(RefCell::new(vec![]));
+        let scheduled = timers.clone();
+        let scheduled_now = now.clone();
+
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let debouncer = universe.debouncer(
+            Duration::from_millis(300),
+            move |delay: Duration, task: Box<dyn FnOnce()>| {
+                scheduled
+                    .borrow_mut()
+                    .push((scheduled_now.get() + delay, task));
+            },
+        );
+        let advance = |by: Duration| {
+            now.set(now.get() + by);
+            let due: Vec<Timer> = {
+                let mut timers = timers.borrow_mut();
+                let (due, waiting) = timers.drain(..).partition(|(at, _)| *at <= now.get());
+                *timers = waiting;
+                due
+            };
+            for (_, task) in due {
+                task();
+            }
+        };
+
+        let search = debouncer.dispatch(Msg::Increment(1));
+        advance(Duration::from_millis(100));
+        assert!(search.cancel());
+        advance(Duration::from_millis(300));
+        assert_eq!(universe.read().counter, 0);
+        assert!(!search.cancel());
+
+        let stale = debouncer.dispatch(Msg::Increment(2));
+        advance(Duration::from_millis(200));
+        let _latest = debouncer.dispatch(Msg::Increment(3));
+        advance(Duration::from_millis(200));
+        assert_eq!(universe.read().counter, 0);
+        assert!(!stale.cancel());
+
+        advance(Duration::from_millis(100));
+        assert_eq!(universe.read().counter, 3);
+    }
+
+    #[test]
+    fn observe_fires_right_away_and_stops_when_dropped() {
+        use std::cell::RefCell;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 2 });
+        let observation = universe.observe(
+            |state| state.counter * 10,
+            move |value| seen_clone.borrow_mut().push(*value),
+        );
+        assert_eq!(*seen.borrow(), vec![20]);
+        assert_eq!(observation.current(), 20);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*seen.borrow(), vec![20, 30]);
+        assert_eq!(observation.current(), 30);
+
+        drop(observation);
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*seen.borrow(), vec![20, 30]);
+    }
+
+    #[test]
+    fn threaded_universe_applies_messages_from_every_thread() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+
+        let notifications = Arc::new(AtomicU64::new(0));
+        let notifications_clone = notifications.clone();
+
+        let universe = AppUniverseThreaded::new(TestAppState { counter: 0 });
+        let subscription = universe.subscribe(Box::new(move |_| {
+            notifications_clone.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let universe = universe.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        universe.msg(Msg::Increment(1));
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(universe.read().counter, 40);
+        assert_eq!(universe.version(), 40);
+        assert_eq!(notifications.load(Ordering::SeqCst), 40);
+
+        assert!(universe.unsubscribe(subscription));
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.load(Ordering::SeqCst), 40);
+    }
+
+    #[test]
+    fn threaded_universe_recovers_from_a_panicking_subscriber() {
+        let universe = AppUniverseThreaded::new(TestAppState { counter: 0 });
+        let subscription = universe.subscribe(Box::new(|universe| {
+            if universe.read().counter == 1 {
+                panic!("subscriber failed");
+            }
+        }));
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            universe.msg(Msg::Increment(1));
+        }));
+        assert!(result.is_err());
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(universe.read().counter, 2);
+        assert_eq!(universe.version(), 2);
+        assert!(universe.unsubscribe(subscription));
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn threaded_dispatch_async_applies_the_message_from_the_spawner_thread() {
+        use std::sync::mpsc;
+
+        let universe = AppUniverseThreaded::new(TestAppState { counter: 0 });
+        assert_eq!(
+            universe.dispatch_async(async { Msg::Increment(1) }),
+            Err(UniverseError::NoSpawner)
+        );
+
+        let (sender, receiver) = mpsc::channel();
+        let sender = std::sync::Mutex::new(sender);
+        universe.set_spawner(move |future| {
+            let sender = sender.lock().unwrap().clone();
+            std::thread::spawn(move || {
+                futures::executor::block_on(future);
+                sender.send(()).unwrap();
+            });
+        });
+
+        universe
+            .dispatch_async(async { Msg::Increment(2) })
+            .unwrap();
+        receiver.recv().unwrap();
+
+        assert_eq!(universe.read().counter, 2);
+        assert_eq!(universe.version(), 1);
+    }
+
+    #[test]
+    fn subscription_guard_unsubscribes_when_dropped() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = Rc::new(std::cell::Cell::new(0));
+        let notifications_clone = notifications.clone();
+
+        let guard = universe.subscribe_guarded(move |_| {
+            notifications_clone.set(notifications_clone.get() + 1);
+        });
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 1);
+
+        drop(guard);
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 1);
+
+        let kept = universe.subscribe_guarded(|_| {}).into_subscription();
+        assert!(universe.try_unsubscribe(kept));
+    }
+
+    #[test]
+    fn read_with_releases_the_borrow_before_returning() {
+        let universe = AppUniverse::new(TestAppState { counter: 2 });
+
+        let label = universe.read_with(|state| format!("{} items", state.counter));
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(label, "2 items");
+        assert_eq!(universe.read_with(|state| state.counter.to_string()), "3");
+    }
+
+    #[test]
+    fn try_read_and_try_msg_fail_instead_of_panicking() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let reading = universe.read();
+        assert_eq!(
+            universe.try_msg(Msg::Increment(1)),
+            Err(UniverseError::AlreadyBorrowed)
+        );
+        drop(reading);
+        assert_eq!(universe.try_msg(Msg::Increment(1)), Ok(()));
+
+        let state = universe.shared_state();
+        let applying = state.borrow_mut();
+        assert_eq!(
+            universe.try_read().err(),
+            Some(UniverseError::AlreadyBorrowed)
+        );
+        drop(applying);
+        assert_eq!(universe.try_read().unwrap().counter, 1);
+    }
+
+    #[test]
+    fn msg_batch_notifies_once_for_every_message() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        universe.msg_batch((0..50).map(|_| Msg::Increment(1)));
+        assert_eq!(universe.read().counter, 50);
+        assert_eq!(universe.version(), 50);
+        assert_eq!(notifications.get(), 1);
+
+        universe.msg_batch(std::iter::empty());
+        assert_eq!(notifications.get(), 1);
+    }
+
+    #[test]
+    fn update_guard_notifies_once_when_dropped() {
+        fn add_two(universe: &AppUniverse<TestAppState>) {
+            universe.msg(Msg::Increment(1));
+            universe.msg(Msg::Increment(1));
+        }
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        let update = universe.begin_update();
+        add_two(&universe);
+        let nested = universe.begin_update();
+        add_two(&universe);
+        drop(nested);
+        assert_eq!(universe.read().counter, 4);
+        assert_eq!(notifications.get(), 0);
+
+        drop(update);
+        assert_eq!(notifications.get(), 1);
+
+        drop(universe.begin_update());
+        assert_eq!(notifications.get(), 1);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 2);
+    }
+
+    #[test]
+    fn nested_dispatches_stop_at_the_depth_limit() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.set_dispatch_depth_limit(3);
+
+        let echo = universe.downgrade();
+        let _echo = universe.subscribe(move |universe| {
+            if universe.read().counter < 4 {
+                echo.upgrade().unwrap().msg(Msg::Increment(1));
+            }
+        });
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(universe.read().counter, 4);
+    }
+
+    #[test]
+    fn try_msg_reports_the_depth_limit() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.set_dispatch_depth_limit(3);
+        let results = Rc::new(RefCell::new(vec![]));
+
+        let (echo, echo_results) = (universe.downgrade(), results.clone());
+        let _echo = universe.subscribe(move |_| {
+            let result = echo.upgrade().unwrap().try_msg(Msg::Increment(1));
+            echo_results.borrow_mut().push(result);
+        });
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(universe.read().counter, 4);
+        assert_eq!(
+            *results.borrow(),
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err(UniverseError::DepthLimitExceeded)
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Dispatches nested deeper than the limit of 3")]
+    #[cfg(debug_assertions)]
+    fn endless_nested_dispatches_panic_in_debug_builds() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.set_dispatch_depth_limit(3);
+
+        let _echo = universe.subscribe(|universe| universe.msg(Msg::Increment(1)));
+
+        universe.msg(Msg::Increment(1));
+    }
+
+    #[test]
+    fn subscribe_select_only_fires_when_the_selection_changes() {
+        use std::cell::RefCell;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let _even = universe.subscribe_select(
+            |state| state.counter % 2 == 0,
+            move |even| seen_clone.borrow_mut().push(*even),
+        );
+
+        universe.msg(Msg::Increment(2));
+        universe.msg(Msg::Increment(1));
+        universe.msg(Msg::Increment(2));
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(*seen.borrow(), vec![false, true]);
+    }
+
+    #[test]
+    fn memo_only_recomputes_when_its_inputs_change() {
+        use std::cell::Cell;
+
+        let computations = Rc::new(Cell::new(0));
+        let computations_clone = computations.clone();
+
+        let universe = AppUniverse::new(TestAppState { counter: 1 });
+        let parity = Memo::new(
+            |state: &TestAppState| state.counter % 2,
+            move |parity| {
+                computations_clone.set(computations_clone.get() + 1);
+                if *parity == 0 {
+                    "even"
+                } else {
+                    "odd"
+                }
+            },
+        );
+        let shared = parity.clone();
+
+        assert_eq!(*parity.get(&universe.read()), "odd");
+        universe.msg(Msg::Increment(2));
+        assert_eq!(*shared.get(&universe.read()), "odd");
+        assert_eq!(computations.get(), 1);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*parity.get(&universe.read()), "even");
+        assert_eq!(*shared.get(&universe.read()), "even");
+        assert_eq!(computations.get(), 2);
+    }
+
+    #[test]
+    fn mapped_universe_only_notifies_for_its_part() {
+        use std::cell::RefCell;
+
+        #[derive(PartialEq)]
+        struct Settings {
+            theme: String,
+        }
+
+        struct App {
+            settings: Settings,
+            clicks: u32,
+        }
+
+        enum AppMsg {
+            Click,
+            SetTheme(&'static str),
+        }
+
+        impl AppUniverseCore for App {
+            type Message = AppMsg;
+
+            fn msg(&mut self, message: Self::Message) {
+                match message {
+                    AppMsg::Click => self.clicks += 1,
+                    AppMsg::SetTheme(theme) => self.settings.theme = theme.to_string(),
+                }
+            }
+        }
+
+        let universe = AppUniverse::new(App {
+            settings: Settings {
+                theme: String::from("light"),
+            },
+            clicks: 0,
+        });
+        let settings = universe.map(|state| &state.settings);
+        let theme = universe.map(|state| state.settings.theme.as_str());
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+        let subscription =
+            theme.subscribe(move |theme| seen_clone.borrow_mut().push(theme.to_string()));
+
+        universe.msg(AppMsg::Click);
+        universe.msg(AppMsg::SetTheme("dark"));
+        universe.msg(AppMsg::SetTheme("dark"));
+
+        assert_eq!(*seen.borrow(), vec!["dark"]);
+        assert_eq!(settings.read().theme, "dark");
+        assert_eq!(universe.read().clicks, 1);
+
+        assert!(theme.unsubscribe(subscription).is_ok());
+    }
+
+    #[test]
+    fn reader_reads_and_subscribes_without_dispatching() {
+        use std::cell::RefCell;
+
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let mut reader = universe.reader();
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+        let subscription =
+            reader.subscribe(move |reader| seen_clone.borrow_mut().push(reader.read().counter));
+
+        universe.msg(Msg::Increment(2));
+        assert_eq!(*seen.borrow(), vec![2]);
+        assert_eq!((reader.read().counter, reader.version()), (2, 1));
+
+        assert!(reader.unsubscribe(subscription).is_ok());
+        universe.msg(Msg::Increment(2));
+        assert_eq!(*seen.borrow(), vec![2]);
+    }
+
+    #[test]
+    fn dispatcher_handle_sends_messages() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+        let dispatcher = universe.dispatcher();
+
+        dispatcher.msg(Msg::Increment(1));
+        dispatcher.msg_batch(vec![Msg::Increment(2), Msg::Increment(3)]);
+        assert_eq!(dispatcher.try_msg(Msg::Increment(4)), Ok(()));
+
+        assert_eq!(universe.read().counter, 10);
+        assert_eq!(universe.version(), 4);
+    }
+
+    #[test]
+    fn subscribe_with_previous_passes_both_states() {
+        use std::cell::RefCell;
+
+        #[derive(Clone)]
+        struct Score(u32);
+
+        impl AppUniverseCore for Score {
+            type Message = u32;
+
+            fn msg(&mut self, points: Self::Message) {
+                self.0 += points;
+            }
+        }
+
+        let steps = Rc::new(RefCell::new(vec![]));
+        let steps_clone = steps.clone();
+
+        let mut universe = AppUniverse::new(Score(0));
+        let _steps = universe.subscribe_with_previous(move |previous, current| {
+            steps_clone.borrow_mut().push((previous.0, current.0))
+        });
+
+        universe.msg(5);
+        universe.msg_batch(vec![1, 2]);
+
+        assert_eq!(*steps.borrow(), vec![(0, 5), (5, 8)]);
+    }
+
+    #[test]
+    fn keyed_subscriptions_replace_each_other_and_unsubscribe_by_key() {
+        use std::cell::RefCell;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        for badge in ["old", "new"] {
+            let seen = seen.clone();
+            let _ = universe.subscribe_keyed("cart-badge", move |_| seen.borrow_mut().push(badge));
+        }
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*seen.borrow(), vec!["new"]);
+
+        assert!(universe.unsubscribe_key("cart-badge"));
+        assert!(!universe.unsubscribe_key("cart-badge"));
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*seen.borrow(), vec!["new"]);
+    }
+
+    #[test]
+    fn keyed_subscriptions_can_be_replaced_from_a_subscriber() {
+        use std::cell::RefCell;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let replaced = Rc::new(std::cell::Cell::new(false));
+        let seen_clone = seen.clone();
+        universe.subscribe_forever(move |mut universe| {
+            if replaced.replace(true) {
+                return;
+            }
+
+            let seen = seen_clone.clone();
+            let _ = universe.subscribe_keyed("cart-badge", move |universe| {
+                seen.borrow_mut().push(universe.read().counter)
+            });
+        });
+        let seen_clone = seen.clone();
+        let _ = universe.subscribe_keyed("cart-badge", move |_| seen_clone.borrow_mut().push(0));
+
+        universe.msg(Msg::Increment(1));
+        assert!(seen.borrow().is_empty());
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(*seen.borrow(), vec![2]);
+        assert_eq!(universe.subscriber_count(), 2);
+    }
+
+    #[test]
+    fn resuming_notifications_catches_up_once() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        universe.pause_notifications();
+        universe.pause_notifications();
+        universe.msg_batch(vec![Msg::Increment(1), Msg::Increment(2)]);
+        universe.msg(Msg::Increment(3));
+        assert_eq!(universe.read().counter, 6);
+        assert_eq!(notifications.get(), 0);
+
+        universe.resume_notifications();
+        assert_eq!(notifications.get(), 1);
+        universe.resume_notifications();
+        assert_eq!(notifications.get(), 1);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 2);
+    }
+
+    #[test]
+    fn strategy_changes_while_paused_survive_resuming() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        universe.pause_notifications();
+        universe.set_notification_strategy(NotificationStrategy::Manual);
+        universe.msg(Msg::Increment(1));
+        universe.resume_notifications();
+        assert_eq!(notifications.get(), 0);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 0);
+        universe.flush();
+        assert_eq!(notifications.get(), 1);
+    }
+
+    #[test]
+    fn pauses_and_updates_can_end_in_any_order() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        universe.pause_notifications();
+        let update = universe.begin_update();
+        universe.msg(Msg::Increment(1));
+        universe.resume_notifications();
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 0);
+
+        drop(update);
+        assert_eq!(notifications.get(), 1);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 2);
+    }
+
+    #[test]
+    fn once_subscriptions_run_a_single_time() {
+        use std::cell::RefCell;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let next = seen.clone();
+        let _ = universe
+            .subscribe_once(move |universe| next.borrow_mut().push(universe.read().counter));
+        let past_three = seen.clone();
+        let _ = universe.subscribe_once_when(
+            |state| state.counter > 3,
+            move |universe| past_three.borrow_mut().push(universe.read().counter * 10),
+        );
+
+        for _ in 0..6 {
+            universe.msg(Msg::Increment(1));
+        }
+
+        assert_eq!(*seen.borrow(), vec![1, 40]);
+    }
+
+    #[test]
+    fn subscribe_when_filters_by_the_predicate() {
+        use std::cell::RefCell;
+
+        let seen = Rc::new(RefCell::new(vec![]));
+        let seen_clone = seen.clone();
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let _even = universe.subscribe_when(
+            |state| state.counter % 2 == 0,
+            move |universe| seen_clone.borrow_mut().push(universe.read().counter),
+        );
+
+        for _ in 0..5 {
+            universe.msg(Msg::Increment(1));
+        }
+
+        assert_eq!(*seen.borrow(), vec![2, 4]);
+    }
+
+    #[test]
+    fn subscription_groups_unsubscribe_together() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let notifications = count_notifications(&mut universe);
+
+        let page_notifications = Rc::new(std::cell::Cell::new(0));
+        let mut page = universe.subscription_group();
+        for _ in 0..3 {
+            let page_notifications = page_notifications.clone();
+            page.add(
+                universe.subscribe(move |_| page_notifications.set(page_notifications.get() + 1)),
+            );
+        }
+        assert_eq!(page.len(), 3);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(page_notifications.get(), 3);
+
+        assert_eq!(page.unsubscribe_all(), 3);
+        assert!(page.is_empty());
+        universe.msg(Msg::Increment(1));
+        assert_eq!(page_notifications.get(), 3);
+        assert_eq!(notifications.get(), 2);
+
+        universe.clear_subscriptions();
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 2);
+    }
+
+    #[test]
+    fn subscriptions_can_be_cleared_from_a_subscriber() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        universe.subscribe_forever(|mut universe| {
+            universe.subscribe_forever(|_| {});
+            universe.clear_subscriptions();
+        });
+        let notifications = count_notifications(&mut universe);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 0);
+        assert_eq!(universe.subscriber_count(), 0);
+
+        universe.msg(Msg::Increment(1));
+        assert_eq!(notifications.get(), 0);
+    }
+
+    #[test]
+    fn introspection_counts_subscribers_and_dispatches() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        assert_eq!(universe.subscriber_count(), 0);
+
+        let subscription = universe.subscribe(|_| {});
+        universe.subscribe_forever(|_| {});
+        assert_eq!(universe.subscriber_count(), 2);
+
+        universe.msg(Msg::Increment(1));
+        universe.msg_batch(vec![Msg::Increment(1), Msg::Increment(1)]);
+        universe.msg_batch(std::iter::empty());
+        assert_eq!((universe.dispatch_count(), universe.version()), (2, 3));
+
+        universe.unsubscribe(subscription).unwrap();
+        assert_eq!(universe.subscriber_count(), 1);
+    }
+
+    #[test]
+    fn subscriber_count_can_be_read_from_a_subscriber() {
+        use std::cell::RefCell;
+
+        let mut universe = AppUniverse::new(TestAppState { counter: 0 });
+        let counts = Rc::new(RefCell::new(vec![]));
+        let counts_clone = counts.clone();
+
+        universe.subscribe_forever(|_| {});
+        let _ = universe.subscribe_with_ctx(Box::new(move |ctx| {
+            let universe = ctx.universe().upgrade().unwrap();
+            counts_clone.borrow_mut().push(universe.subscriber_count());
+
+            ctx.unsubscribe_self();
+            counts_clone.borrow_mut().push(universe.subscriber_count());
+        }));
+
+        universe.msg(Msg::Increment(1));
+
+        assert_eq!(*counts.borrow(), vec![2, 1]);
+        assert_eq!(universe.subscriber_count(), 1);
+    }
+
+    #[test]
+    fn versioned_reads_report_changes_since_the_last_frame() {
+        let universe = AppUniverse::new(TestAppState { counter: 0 });
+
+        let mut last_frame = {
+            let frame = universe.read_versioned();
+            assert!(!frame.has_changed(&0));
+            frame.version()
+        };
+
+        universe.msg(Msg::Increment(2));
+
+        let frame = universe.read_versioned();
+        assert!(frame.has_changed(&last_frame));
+        assert_eq!(frame.counter, 2);
+        last_frame = frame.version();
+        assert!(!frame.has_changed(&last_frame));
+    }
+
+    #[test]
+    fn replacing_the_state_notifies_once() {
+        let mut universe = AppUniverse::new(TestAppState { counter: 3 });
+        let notifications = count_notifications(&mut universe);
+
+        universe.replace_state(TestAppState { counter: 40 });
+        assert_eq!(universe.read().counter, 40);
+        assert_eq!((universe.version(), notifications.get()), (1, 1));
+
+        universe.reset_with(|| TestAppState { counter: 0 });
+        assert_eq!(universe.read().counter, 0);
+        assert_eq!((universe.version(), notifications.get()), (2, 2));
+    }
 }
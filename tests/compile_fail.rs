@@ -0,0 +1,6 @@
+#[test]
+#[cfg(feature = "async")]
+fn threaded_dispatch_async_rejects_non_send_messages() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,21 @@
+use app_universe::{AppUniverseCore, AppUniverseThreaded};
+use std::rc::Rc;
+
+struct Counter {
+    value: u32,
+}
+
+struct Add(Rc<u32>);
+
+impl AppUniverseCore for Counter {
+    type Message = Add;
+
+    fn msg(&mut self, Add(amount): Add) {
+        self.value += *amount;
+    }
+}
+
+fn main() {
+    let universe = AppUniverseThreaded::new(Counter { value: 0 });
+    let _ = universe.dispatch_async(async { Add(Rc::new(1)) });
+}